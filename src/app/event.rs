@@ -1,7 +1,9 @@
 use crate::app::AppResult;
-use crate::git::repo::{Log, RepoStatus};
+use crate::git::repo::{History, RepoStatus};
 use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
-use std::sync::mpsc;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, mpsc};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -19,7 +21,14 @@ pub enum Event {
   /// The current state of a Repo has changed.
   RepoStatusChange(String, RepoStatus),
   /// The Repo git actions are complete
-  RepoStatusComplete(String, Vec<Log>),
+  RepoStatusComplete(String, History),
+  /// A difftool has exited, or failed to start.
+  DifftoolComplete(Result<(), String>),
+  /// The full shas of the logs of a repo that touch a path have been found.
+  TouchedComplete(String, String, HashSet<String>),
+  /// The logs of a repo reachable from the commit seen in it have been found,
+  /// or `None` when that commit is no longer in the repo.
+  ReadComplete(String, String, Option<HashSet<String>>),
 }
 
 /// Terminal event handler.
@@ -31,6 +40,28 @@ pub struct EventHandler {
   receiver: mpsc::Receiver<Event>,
   /// Event handler thread.
   pub handler: thread::JoinHandle<()>,
+  /// Stops the handler thread from reading terminal events.
+  pause: Arc<Pause>,
+}
+
+#[derive(Debug, Default)]
+struct Pause {
+  paused: AtomicBool,
+  /// Held by the handler thread while it reads terminal events.
+  polling: Mutex<()>,
+}
+
+/// Terminal events are not read until this is dropped.
+#[derive(Debug)]
+pub struct PausedEvents<'a> {
+  _polling: MutexGuard<'a, ()>,
+  paused: &'a AtomicBool,
+}
+
+impl Drop for PausedEvents<'_> {
+  fn drop(&mut self) {
+    self.paused.store(false, Ordering::SeqCst);
+  }
 }
 
 impl EventHandler {
@@ -38,11 +69,20 @@ impl EventHandler {
   pub fn new(tick_rate: u64) -> Self {
     let tick_rate = Duration::from_millis(tick_rate);
     let (sender, receiver) = mpsc::channel();
+    let pause = Arc::new(Pause::default());
     let handler = {
       let sender = sender.clone();
+      let pause = Arc::clone(&pause);
       thread::spawn(move || {
         let mut last_tick = Instant::now();
         loop {
+          if pause.paused.load(Ordering::SeqCst) {
+            thread::sleep(tick_rate);
+            last_tick = Instant::now();
+            continue;
+          }
+          let _polling = pause.polling.lock().expect("event polling lock poisoned");
+
           let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or(tick_rate);
@@ -70,6 +110,21 @@ impl EventHandler {
       sender,
       receiver,
       handler,
+      pause,
+    }
+  }
+
+  /// Stops reading terminal events until the returned guard is dropped, so
+  /// that another program may read from the terminal.
+  pub fn pause(&self) -> PausedEvents<'_> {
+    self.pause.paused.store(true, Ordering::SeqCst);
+    PausedEvents {
+      _polling: self
+        .pause
+        .polling
+        .lock()
+        .expect("event polling lock poisoned"),
+      paused: &self.pause.paused,
     }
   }
 
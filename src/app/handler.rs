@@ -1,19 +1,76 @@
-use crate::app::{App, AppResult, SelectedModal, SelectedPane};
+use crate::app::{App, AppResult, Event, SelectedModal, SelectedPane};
+use crate::git::patch::Patch;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::sync::mpsc;
 
 /// Handles the key events and updates the state of [`App`].
-pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+pub fn handle_key_events(key_event: KeyEvent, app: &mut App, sender: &mpsc::Sender<Event>) -> AppResult<()> {
+  let viewing_diff = app.diff_view.is_some();
+  let filtering = !app.filter.is_empty();
   match (&app.selected_pane, &app.modal, key_event.code) {
     // Calendar selection widget
     (_, SelectedModal::Calendar, KeyCode::Up | KeyCode::Char('k')) => decrement_calendar_week(app),
     (_, SelectedModal::Calendar, KeyCode::Down | KeyCode::Char('j')) => increment_calendar_week(app),
     (_, SelectedModal::Calendar, KeyCode::Left | KeyCode::Char('h')) => decrement_calendar_day(app),
     (_, SelectedModal::Calendar, KeyCode::Right | KeyCode::Char('l')) => increment_calendar_day(app),
+    (_, SelectedModal::Calendar, KeyCode::Char('[') | KeyCode::PageUp) => app.calendar_state.decrement_months(1),
+    (_, SelectedModal::Calendar, KeyCode::Char(']') | KeyCode::PageDown) => app.calendar_state.increment_months(1),
+    (_, SelectedModal::Calendar, KeyCode::Char('t')) => app.calendar_state.go_to_today(),
+    (_, SelectedModal::Calendar, KeyCode::Char('v')) => app.calendar_state.toggle_anchor(),
+    (_, SelectedModal::Calendar, KeyCode::Char('a')) => app.activity_all_repos = !app.activity_all_repos,
+    (_, SelectedModal::Calendar, KeyCode::Char('u')) => {
+      select_calendar_until(app);
+      close_modal(app);
+    }
+    (_, SelectedModal::Calendar, KeyCode::Char('U')) => {
+      app.until = None;
+      close_modal(app);
+    }
     (_, SelectedModal::Calendar, KeyCode::Enter) => {
       select_calendar_day(app);
       close_modal(app);
     }
 
+    // Search prompt
+    (_, SelectedModal::Search, KeyCode::Enter) => app.apply_search(sender),
+    (_, SelectedModal::Search, KeyCode::Esc) => close_modal(app),
+    (_, SelectedModal::Search, KeyCode::Backspace) => {
+      app.search.pop();
+    }
+    (_, SelectedModal::Search, KeyCode::Char(c)) => app.search.push(c),
+    (_, SelectedModal::Search, _) => {}
+
+    // Builtin difftool
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Up | KeyCode::Char('k')) if viewing_diff => {
+      app.scroll_diff_view(-1)
+    }
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Down | KeyCode::Char('j')) if viewing_diff => {
+      app.scroll_diff_view(1)
+    }
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char(' ') | KeyCode::Char('f')) if viewing_diff => {
+      app.scroll_diff_view(10)
+    }
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('b')) if viewing_diff => app.scroll_diff_view(-10),
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char(']')) if viewing_diff => {
+      app.jump_diff_view(Patch::files, true)
+    }
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('[')) if viewing_diff => {
+      app.jump_diff_view(Patch::files, false)
+    }
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('n')) if viewing_diff => {
+      app.jump_diff_view(Patch::hunks, true)
+    }
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('N')) if viewing_diff => {
+      app.jump_diff_view(Patch::hunks, false)
+    }
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Esc | KeyCode::Char('d')) if viewing_diff => {
+      app.diff_view = None
+    }
+
+    // Scroll the detail pane
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('K')) => app.scroll_detail(-1),
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('J')) => app.scroll_detail(1),
+
     // Scroll through lists
     (SelectedPane::Diff, _, KeyCode::Up | KeyCode::Char('k')) => decrement_selected_log(app, 1),
     (SelectedPane::Diff, _, KeyCode::Down | KeyCode::Char('j')) => increment_selected_log(app, 1),
@@ -32,20 +89,49 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
     (SelectedPane::Diff, _, KeyCode::Tab) => app.selected_pane = SelectedPane::Repos,
     (SelectedPane::Repos, _, KeyCode::Tab) => app.selected_pane = SelectedPane::Diff,
 
+    // filter
+    (_, SelectedModal::None, KeyCode::Char('/')) => app.open_search(),
+    (_, SelectedModal::None, KeyCode::Esc) if filtering => app.clear_filter(sender),
+
+    // timeline
+    (_, SelectedModal::None, KeyCode::Char('a')) => {
+      app.toggle_timeline();
+      app.selected_pane = SelectedPane::Diff;
+    }
+
+    // expand merge
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('e')) => app.toggle_selected_merge(),
+
+    // sort repos
+    (_, SelectedModal::None, KeyCode::Char('S')) => app.cycle_sort(),
+
+    // collapse group
+    (SelectedPane::Repos, SelectedModal::None, KeyCode::Enter) => app.toggle_selected_group(),
+
+    // commit detail
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Enter) => app.toggle_detail(),
+
+    // seen
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('m')) => app.mark_selected_seen(),
+    (_, SelectedModal::None, KeyCode::Char('M')) => app.mark_all_seen(),
+
     // open diff
-    (SelectedPane::Diff, _, KeyCode::Char('d')) => open_git_difftool(app),
+    (SelectedPane::Diff, _, KeyCode::Char('d')) => app.open_difftool(sender),
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('D')) => app.open_commit_difftool(sender),
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('v')) => app.toggle_range_mark(),
+    (SelectedPane::Diff, SelectedModal::None, KeyCode::Char('o')) => app.open_commit_page(sender),
+    (_, SelectedModal::None, KeyCode::Char('O')) => app.open_branch_page(sender),
 
     // update
     (SelectedPane::Diff, _, KeyCode::Char('r')) => app.reset(),
     (SelectedPane::Repos, _, KeyCode::Char('r')) => app.reset(),
+    (_, SelectedModal::None, KeyCode::Char('R')) => app.retry_selected_repo(),
 
     // exit application on ESC, q, or Ctrl-D
     (_, SelectedModal::None, KeyCode::Esc) => app.running = false,
     (_, _, KeyCode::Char('q') | KeyCode::Char('Q')) => app.running = false,
-    (_, _, KeyCode::Char('d') | KeyCode::Char('D')) => {
-      if key_event.modifiers == KeyModifiers::CONTROL {
-        app.running = false;
-      }
+    (_, _, KeyCode::Char('d') | KeyCode::Char('D')) if key_event.modifiers == KeyModifiers::CONTROL => {
+      app.running = false;
     }
 
     // modal management
@@ -73,18 +159,17 @@ fn decrement_selected_log(app: &mut App, count: usize) {
       app.selected_repo_state.select(Some(0))
     };
   }
+  app.refresh_selection();
 }
 
 fn increment_selected_log(app: &mut App, count: usize) {
-  let selected_repo_index = app.repo_state.selected().unwrap();
-  let (_id, selected_repo) = app.repos.get_index(selected_repo_index).unwrap();
-
-  let max_log: usize = selected_repo.logs.len() - 1;
+  let max_log: usize = app.visible_log_count().saturating_sub(1);
 
   if let Some(current) = app.selected_repo_state.selected() {
     let next = std::cmp::min(current + count, max_log);
     app.selected_repo_state.select(Some(next));
   }
+  app.refresh_selection();
 }
 
 fn decrement_repos(app: &mut App, count: usize) {
@@ -96,15 +181,17 @@ fn decrement_repos(app: &mut App, count: usize) {
       app.repo_state.select(Some(0))
     };
   }
+  app.refresh_selection();
 }
 
 fn increment_repos(app: &mut App, count: usize) {
   app.selected_repo_state.select(Some(0));
-  let max_repos: usize = app.repos.len() - 1;
+  let max_repos: usize = app.repo_row_count().saturating_sub(1);
   if let Some(current) = app.repo_state.selected() {
     let next = std::cmp::min(current + count, max_repos);
     app.repo_state.select(Some(next));
   }
+  app.refresh_selection();
 }
 
 // // // Calendar
@@ -125,8 +212,38 @@ fn increment_calendar_week(app: &mut App) {
   app.calendar_state.increment(7);
 }
 
+/// Shows logs from the selected day, or over the selected range of days.
 fn select_calendar_day(app: &mut App) {
-  app.since = app.calendar_state.to_utc_datetime();
+  let (since, until) = app.calendar_state.selection();
+  let needs_logs = app.repos.values().any(|repo| {
+    since
+      < repo
+        .log_limits(&app.since, app.margin, app.max_commits)
+        .cutoff
+  });
+
+  app.since = since;
+  for repo in app.repos.values_mut() {
+    repo.since = None;
+  }
+  if until.is_some() {
+    app.until = until;
+  } else if app.until.is_some_and(|until| until <= since) {
+    app.until = None;
+  }
+  if needs_logs {
+    app.reset();
+  }
+}
+
+/// Dims logs after the selected day, moving `since` back to the start of the
+/// day when it is later.
+fn select_calendar_until(app: &mut App) {
+  let start = app.calendar_state.to_utc_datetime();
+  if app.since >= start {
+    select_calendar_day(app);
+  }
+  app.until = Some(app.calendar_state.end_of_day());
 }
 
 // // // Modals
@@ -138,14 +255,3 @@ fn open_modal(app: &mut App, modal: SelectedModal) {
 fn close_modal(app: &mut App) {
   app.modal = SelectedModal::None;
 }
-
-// // // Diff
-
-fn open_git_difftool(app: &App) {
-  let selected_repo_index = app.repo_state.selected().unwrap();
-  let (_id, selected_repo) = app.repos.get_index(selected_repo_index).unwrap();
-  let selected_log = app.selected_repo_state.selected().unwrap();
-  let log = &selected_repo.logs[selected_log];
-
-  app.difftool.open(&app.root_path, selected_repo, log);
-}
@@ -2,9 +2,14 @@ pub mod event;
 pub mod handler;
 
 pub use self::event::{Event, EventHandler};
-use crate::difftool::Difftool;
-use crate::git::repo::{Log, Repo, RepoStatus};
+use crate::difftool::{self, DiffRange, Difftool};
+use crate::filter::LogFilter;
+use crate::forge::{Forge, OPEN_COMMAND};
+use crate::git;
+use crate::git::patch::{self, Patch};
+use crate::git::repo::{FileChange, History, Log, Repo, RepoSort, RepoStatus};
 use crate::manifest::Manifest;
+use crate::seen::Seen;
 use crate::semaphore::Semaphore;
 use crate::theme::ColorTheme;
 use crate::ui;
@@ -13,9 +18,10 @@ use indexmap::map::IndexMap;
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::widgets::TableState;
-use std::cmp::Ordering;
+use std::cell::{Ref, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::mpsc;
 
@@ -42,31 +48,190 @@ pub enum SelectedModal {
   #[default]
   None,
   Calendar,
+  /// The `/` prompt, editing the filter.
+  Search,
 }
 
 /// Maximum number of concurrent git operations.
-const MAX_CONCURRENT_GIT_OPS: usize = 5;
+pub(crate) const MAX_CONCURRENT_GIT_OPS: usize = 5;
+
+/// A row in the Diff pane.
+#[derive(Debug)]
+pub struct LogRow<'a> {
+  pub id: &'a str,
+  pub repo: &'a Repo,
+  pub log: &'a Log,
+  /// Whether this log was brought in by the merge above it.
+  pub nested: bool,
+}
+
+/// Where the log of a row in the Diff pane is found in the app's repos.
+#[derive(Clone, Copy, Debug)]
+struct RowIndex {
+  repo: usize,
+  log: usize,
+  /// The index of the log in the `merged` logs of the log above, when nested.
+  merged: Option<usize>,
+}
+
+/// Where the repos of a row in the Repos pane are found in the app's repos.
+#[derive(Clone, Debug)]
+enum RepoRowIndex {
+  Group {
+    name: String,
+    collapsed: bool,
+    repos: Vec<usize>,
+  },
+  Repo {
+    repo: usize,
+    grouped: bool,
+  },
+}
+
+/// A row in the Repos pane.
+#[derive(Debug)]
+pub enum RepoRow<'a> {
+  /// The header of a group of remotes, followed by its repos unless collapsed.
+  Group {
+    name: &'a str,
+    collapsed: bool,
+    repos: Vec<(&'a String, &'a Repo)>,
+  },
+  Repo {
+    id: &'a String,
+    repo: &'a Repo,
+    /// Whether the repo is listed beneath a group header.
+    grouped: bool,
+  },
+}
+
+impl RepoRow<'_> {
+  /// Identifies the row across re-sorts, by group name or repo id.
+  fn key(&self) -> (bool, String) {
+    match self {
+      RepoRow::Group { name, .. } => (true, name.to_string()),
+      RepoRow::Repo { id, .. } => (false, id.to_string()),
+    }
+  }
+}
+
+/// The commit shown in the detail pane.
+#[derive(Debug)]
+pub struct CommitDetail {
+  /// The files changed by the commit, or why they could not be read.
+  pub changes: Result<Vec<FileChange>, String>,
+  pub log: Log,
+  pub scroll: u16,
+}
+
+/// A diff shown by the builtin difftool, in place of the Diff pane.
+#[derive(Debug)]
+pub struct DiffView {
+  /// The sha of the log the diff was opened from.
+  pub full_sha: String,
+  /// The patch, or why it could not be read.
+  pub patch: Result<Patch, String>,
+  pub ref_from: String,
+  pub ref_to: String,
+  /// The index of the first line shown.
+  pub scroll: usize,
+}
+
+impl DiffView {
+  fn new(root_path: &Path, repo: &Repo, log: &Log, range: &DiffRange) -> Self {
+    let ref_from = range.from.clone();
+    let ref_to = range.ref_to(repo);
+    let patch = repo
+      .path(root_path)
+      .and_then(|path| git::patch::patch(&path, &ref_from, &ref_to))
+      .map_err(|err| err.to_string());
+    DiffView {
+      full_sha: log.full_sha.clone(),
+      patch,
+      ref_from,
+      ref_to,
+      scroll: 0,
+    }
+  }
+}
+
+/// How a difftool opens a diff.
+enum DifftoolLaunch {
+  View(DiffView),
+  Terminal(std::process::Command),
+  Background(std::process::Command),
+}
 
 #[derive(Debug)]
 pub struct App {
+  /// Whether the calendar shades days by the commits of every repo, rather
+  /// than only the selected repo.
+  pub activity_all_repos: bool,
   pub calendar_state: crate::widget::calendar::CalendarState,
+  /// Groups of remotes whose repos are hidden in the Repos pane.
+  pub collapsed: HashSet<String>,
+  pub detail: Option<CommitDetail>,
+  pub diff_view: Option<DiffView>,
   pub difftool: Difftool,
+  /// Why the last difftool failed, until another is opened.
+  pub difftool_failure: Option<String>,
+  /// Merges whose merged commits are shown, by repo id and sha.
+  pub expanded: HashSet<(String, String)>,
+  /// Narrows the logs shown in the Diff pane.
+  pub filter: LogFilter,
+  /// The day that weeks start on in the calendar.
+  pub first_weekday: chrono::Weekday,
+  /// Repo ids in the order that their remotes appear in the manifest.
+  manifest_order: Vec<String>,
+  pub margin: chrono::Duration,
+  pub max_commits: usize,
   pub modal: SelectedModal,
+  /// The log marked with `v` as one end of a range to diff, by repo id and sha.
+  pub range_mark: Option<(String, String)>,
   pub repo_state: TableState,
   pub repos: IndexMap<String, Repo>,
   pub root_path: PathBuf,
+  /// The rows of the Diff pane, by the index of the repo they were built
+  /// for, or `None` in the timeline. Cleared whenever the logs, filter, sort
+  /// or mode change.
+  log_rows: RefCell<Option<(Option<usize>, Vec<RowIndex>)>>,
+  /// The rows of the Repos pane. Cleared whenever the repos are sorted or a
+  /// group is collapsed.
+  repo_row_indices: RefCell<Option<Vec<RepoRowIndex>>>,
   pub running: bool,
+  /// The query being typed into the `/` prompt.
+  pub search: String,
+  /// Why the query in the `/` prompt could not be applied.
+  pub search_error: Option<String>,
+  /// The newest log seen in each repo, and when dyd was last run.
+  pub seen: Seen,
   pub selected_pane: SelectedPane,
   pub selected_repo_state: TableState,
   pub since: chrono::DateTime<chrono::Utc>,
+  pub sort: RepoSort,
   pub state: AppState,
   pub theme: ColorTheme,
+  pub timeline: bool,
   pub timezone_offset: chrono::offset::FixedOffset,
+  /// Logs newer than this are dimmed, and diffs open to the newest log
+  /// before it rather than to the tip.
+  pub until: Option<chrono::DateTime<chrono::Utc>>,
+  /// Ids of repos whose seen commit has changed, to find the logs reachable
+  /// from it on the next tick.
+  reread: Vec<String>,
+  /// Ids of repos to update on the next tick, independently of the others.
+  retry: Vec<String>,
+  /// A difftool to run in the terminal once the UI has been suspended.
+  terminal_difftool: Option<std::process::Command>,
+  /// The full shas of the logs of each repo that touch the filter's path.
+  touched: HashMap<String, HashSet<String>>,
   semaphore: Arc<Semaphore>,
 }
 
 impl App {
-  pub fn from_manifest(manifest: Manifest, theme: ColorTheme) -> Self {
+  pub fn from_manifest(manifest: Manifest, seen: Seen, theme: ColorTheme) -> Self {
+    let margin = manifest.margin();
+    let sort = manifest.repo_sort();
     let repos: IndexMap<String, Repo> = manifest
       .remotes
       .into_iter()
@@ -75,8 +240,12 @@ impl App {
 
     let mut repo_state = TableState::default();
     if !repos.is_empty() {
-      repo_state.select(Some(0))
+      let grouped = sort == RepoSort::Group && repos.values().any(|repo: &Repo| repo.group.is_some());
+      // The first row is a group header when grouped, so start on the repo
+      // beneath it.
+      repo_state.select(Some(if grouped { 1 } else { 0 }))
     }
+    let manifest_order = repos.keys().cloned().collect();
 
     let mut selected_repo_state = TableState::default();
     selected_repo_state.select(Some(0));
@@ -88,27 +257,71 @@ impl App {
     let offset = chrono::offset::FixedOffset::east_opt(offset_sec).unwrap();
 
     Self {
+      activity_all_repos: true,
       calendar_state,
+      collapsed: HashSet::new(),
+      detail: None,
+      diff_view: None,
       difftool: manifest.difftool,
+      difftool_failure: None,
+      expanded: HashSet::new(),
+      manifest_order,
+      filter: LogFilter::default(),
+      first_weekday: chrono::Weekday::Sun,
+      margin,
+      max_commits: manifest.max_commits,
       modal: SelectedModal::default(),
+      range_mark: None,
       repo_state,
       repos,
+      reread: vec![],
+      retry: vec![],
+      terminal_difftool: None,
       root_path: manifest.root.unwrap(),
+      log_rows: RefCell::new(None),
+      repo_row_indices: RefCell::new(None),
       running: true,
+      search: String::new(),
+      search_error: None,
+      seen,
       selected_pane: SelectedPane::default(),
       selected_repo_state,
       semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_GIT_OPS)),
       since,
+      sort,
       state: AppState::default(),
       theme,
+      timeline: false,
       timezone_offset: offset,
+      touched: HashMap::new(),
+      until: manifest.until_datetime,
     }
   }
   pub fn tick(&mut self, sender: mpsc::Sender<Event>) -> AppResult<()> {
     if self.state == AppState::Init {
-      self.update(sender)?;
+      self.update(sender.clone())?;
       self.state = AppState::Checking;
     }
+    for id in std::mem::take(&mut self.retry) {
+      if let Some(repo) = self.repos.get(&id) {
+        let limits = repo.log_limits(&self.since, self.margin, self.max_commits);
+        repo.update(
+          &id,
+          &self.root_path,
+          limits,
+          sender.clone(),
+          Arc::clone(&self.semaphore),
+        )?;
+      }
+    }
+    for id in std::mem::take(&mut self.reread) {
+      if let Some(repo) = self.repos.get(&id) {
+        let limits = repo.log_limits(&self.since, self.margin, self.max_commits);
+        self
+          .seen
+          .spawn_read(&id, &self.root_path, repo, limits, sender.clone());
+      }
+    }
     Ok(())
   }
 
@@ -120,26 +333,695 @@ impl App {
       .constraints([Constraint::Percentage(75), Constraint::Percentage(25)].as_ref())
       .split(size);
 
+    let main = if self.modal == SelectedModal::Search || !self.filter.is_empty() {
+      let main = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(0)
+        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+        .split(layout[0]);
+      frame.render_widget(ui::search::render(self), main[0]);
+      main[1]
+    } else {
+      layout[0]
+    };
+
     let sidebar = Layout::default()
       .direction(Direction::Vertical)
       .margin(0)
       .constraints([Constraint::Ratio(7, 10), Constraint::Ratio(3, 10)].as_ref())
       .split(layout[1]);
 
-    frame.render_stateful_widget(ui::diff::render(self), layout[0], &mut self.selected_repo_state.clone());
+    match (
+      self.selected_repo().map(|(_id, repo)| &repo.status),
+      &self.diff_view,
+      &self.detail,
+    ) {
+      (Some(RepoStatus::Failed(reason)), _, _) if !self.timeline => {
+        frame.render_widget(ui::diff::render_failure(self, reason), main)
+      }
+      (_, Some(view), _) => frame.render_widget(ui::patch::render(self, view, main.height), main),
+      (_, None, Some(detail)) => {
+        let main = Layout::default()
+          .direction(Direction::Vertical)
+          .margin(0)
+          .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+          .split(main);
+        frame.render_stateful_widget(ui::diff::render(self), main[0], &mut self.selected_repo_state.clone());
+        frame.render_widget(ui::detail::render(self, detail), main[1]);
+      }
+      (_, None, None) => {
+        frame.render_stateful_widget(ui::diff::render(self), main, &mut self.selected_repo_state.clone())
+      }
+    }
     frame.render_stateful_widget(ui::repos::render(self), sidebar[0], &mut self.repo_state.clone());
     frame.render_widget(ui::help::render(self), sidebar[1]);
 
     ui::modal::render(self, frame);
   }
 
+  /// Rows of the Repos pane. When sorted by group, repos are listed beneath
+  /// a header for their group, followed by those without a group.
+  pub fn repo_rows(&self) -> Vec<RepoRow<'_>> {
+    let indices = self.repo_row_indices();
+    indices.iter().map(|index| self.repo_row(index)).collect()
+  }
+
+  pub fn repo_row_count(&self) -> usize {
+    self.repo_row_indices().len()
+  }
+
+  fn repo_row_indices(&self) -> Ref<'_, Vec<RepoRowIndex>> {
+    if self.repo_row_indices.borrow().is_none() {
+      *self.repo_row_indices.borrow_mut() = Some(self.build_repo_rows());
+    }
+    Ref::map(self.repo_row_indices.borrow(), |rows| {
+      rows.as_ref().expect("built above")
+    })
+  }
+
+  fn build_repo_rows(&self) -> Vec<RepoRowIndex> {
+    let mut groups: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    let mut ungrouped = vec![];
+    for (index, repo) in self.repos.values().enumerate() {
+      match &repo.group {
+        Some(group) if self.sort == RepoSort::Group => groups.entry(group).or_default().push(index),
+        _ => ungrouped.push(RepoRowIndex::Repo {
+          repo: index,
+          grouped: false,
+        }),
+      }
+    }
+
+    let mut rows = vec![];
+    for (name, repos) in groups {
+      let collapsed = self.collapsed.contains(name);
+      rows.push(RepoRowIndex::Group {
+        name: name.to_string(),
+        collapsed,
+        repos: repos.clone(),
+      });
+      if !collapsed {
+        rows.extend(
+          repos
+            .into_iter()
+            .map(|repo| RepoRowIndex::Repo { repo, grouped: true }),
+        );
+      }
+    }
+    rows.extend(ungrouped);
+    rows
+  }
+
+  fn repo_row(&self, index: &RepoRowIndex) -> RepoRow<'_> {
+    let repo = |index: usize| {
+      self
+        .repos
+        .get_index(index)
+        .expect("rows are cleared when repos change")
+    };
+    match index {
+      RepoRowIndex::Group { collapsed, repos, .. } => RepoRow::Group {
+        name: repo(repos[0]).1.group.as_deref().unwrap_or_default(),
+        collapsed: *collapsed,
+        repos: repos.iter().map(|index| repo(*index)).collect(),
+      },
+      RepoRowIndex::Repo { repo: index, grouped } => {
+        let (id, repo) = repo(*index);
+        RepoRow::Repo {
+          id,
+          repo,
+          grouped: *grouped,
+        }
+      }
+    }
+  }
+
+  /// The index in `repos` of the repo selected in the Repos pane.
+  fn selected_repo_index(&self) -> Option<usize> {
+    let index = self.repo_state.selected()?;
+    match self.repo_row_indices().get(index)? {
+      RepoRowIndex::Repo { repo, .. } => Some(*repo),
+      RepoRowIndex::Group { .. } => None,
+    }
+  }
+
+  /// The repo selected in the Repos pane, or `None` when a group header is
+  /// selected.
+  pub fn selected_repo(&self) -> Option<(&String, &Repo)> {
+    self.repos.get_index(self.selected_repo_index()?)
+  }
+
+  /// Shows or hides the repos of the selected group header.
+  pub fn toggle_selected_group(&mut self) {
+    let Some(index) = self.repo_state.selected() else {
+      return;
+    };
+    let name = match self.repo_row_indices().get(index) {
+      Some(RepoRowIndex::Group { name, .. }) => name.clone(),
+      _ => return,
+    };
+    if !self.collapsed.remove(&name) {
+      self.collapsed.insert(name);
+    }
+    self.clear_repo_rows();
+    self.refresh_selection();
+  }
+
+  /// Updates the selected repo on the next tick, leaving the others as-is.
+  pub fn retry_selected_repo(&mut self) {
+    let Some(id) = self.selected_repo().map(|(id, _repo)| id.clone()) else {
+      return;
+    };
+    if let Some(repo) = self.repos.get_mut(&id) {
+      repo.status = RepoStatus::Checking;
+      self.retry.push(id);
+    }
+  }
+
+  /// Opens the detail pane for the selected log, or closes it when open.
+  pub fn toggle_detail(&mut self) {
+    if self.detail.take().is_none() {
+      self.detail = self.selected_detail();
+      self.mark_selected_seen();
+    }
+  }
+
+  /// Marks the selected log, and everything older in its repo, as read.
+  pub fn mark_selected_seen(&mut self) {
+    let Some(row) = self.selected_row() else {
+      return;
+    };
+    let (id, log) = (row.id.to_string(), row.log.clone());
+    if self.seen.mark(&id, &log) {
+      self.reread.push(id);
+      self.save_seen();
+    }
+  }
+
+  /// Marks every repo as read, up to its newest log.
+  pub fn mark_all_seen(&mut self) {
+    let newest: Vec<(String, Log)> = self
+      .repos
+      .iter()
+      .filter_map(|(id, repo)| repo.logs.first().map(|log| (id.clone(), log.clone())))
+      .collect();
+    let mut changed = false;
+    for (id, log) in newest {
+      if self.seen.mark(&id, &log) {
+        self.reread.push(id);
+        changed = true;
+      }
+    }
+    if changed {
+      self.save_seen();
+    }
+  }
+
+  fn save_seen(&self) {
+    if let Err(err) = self.seen.save() {
+      log::error!("failed saving seen commits: {err}");
+    }
+  }
+
+  /// The number of logs of a repo between `since` and `until` that have not
+  /// been seen.
+  pub fn unread_count(&self, id: &str, repo: &Repo) -> usize {
+    repo
+      .logs
+      .iter()
+      .filter(|log| self.in_window(repo, log) && !repo.excludes(log) && self.seen.is_unread(id, log))
+      .count()
+  }
+
+  /// The number of commits on each local day, in every repo or only the
+  /// selected repo, for shading the calendar.
+  pub fn activity(&self) -> HashMap<chrono::NaiveDate, usize> {
+    let repos: Vec<&Repo> = if self.activity_all_repos {
+      self.repos.values().collect()
+    } else {
+      self
+        .selected_repo()
+        .map(|(_id, repo)| repo)
+        .into_iter()
+        .collect()
+    };
+    let mut activity = HashMap::new();
+    for repo in repos {
+      for log in repo.logs.iter().filter(|log| !repo.excludes(log)) {
+        *activity
+          .entry(log.commit_datetime.with_timezone(&Local).date_naive())
+          .or_default() += 1;
+      }
+    }
+    activity
+  }
+
+  /// Whether `log` falls between `since` and `until`. Logs outside of the
+  /// window are dimmed.
+  pub fn in_window(&self, repo: &Repo, log: &Log) -> bool {
+    repo.in_window(log, &self.since, self.until.as_ref())
+  }
+
+  /// Brings the detail, the builtin difftool and the range mark in line with
+  /// the selected log, after the selection or the rows of the Diff pane change.
+  pub fn refresh_selection(&mut self) {
+    self.refresh_detail();
+    self.refresh_diff_view();
+    self.refresh_range_mark();
+  }
+
+  /// Re-reads the detail pane when the selection has moved to another log.
+  fn refresh_detail(&mut self) {
+    let Some(detail) = &self.detail else {
+      return;
+    };
+    let selected = self.selected_log().map(|(_repo, log)| &log.full_sha);
+    if selected != Some(&detail.log.full_sha) {
+      self.detail = self.selected_detail();
+    }
+  }
+
+  /// Opens the `/` prompt, starting from the current filter.
+  pub fn open_search(&mut self) {
+    self.search = self.filter.to_string();
+    self.search_error = None;
+    self.modal = SelectedModal::Search;
+  }
+
+  /// Filters the Diff pane by the query in the `/` prompt, leaving the
+  /// prompt open when the query is invalid.
+  pub fn apply_search(&mut self, sender: &mpsc::Sender<Event>) {
+    match LogFilter::parse(&self.search) {
+      Ok(filter) => {
+        self.set_filter(filter, sender);
+        self.modal = SelectedModal::None;
+      }
+      Err(err) => self.search_error = Some(err.to_string()),
+    }
+  }
+
+  pub fn clear_filter(&mut self, sender: &mpsc::Sender<Event>) {
+    self.set_filter(LogFilter::default(), sender);
+  }
+
+  fn set_filter(&mut self, filter: LogFilter, sender: &mpsc::Sender<Event>) {
+    self.filter = filter;
+    self.touched.clear();
+    self.clear_rows();
+    for (id, repo) in &self.repos {
+      self
+        .filter
+        .spawn_touched(id, &self.root_path, repo, sender.clone());
+      let limits = repo.log_limits(&self.since, self.margin, self.max_commits);
+      self
+        .seen
+        .spawn_read(id, &self.root_path, repo, limits, sender.clone());
+    }
+    self.selected_repo_state.select(Some(0));
+    self.refresh_selection();
+  }
+
+  /// Records the logs of a repo that touch `path`, unless the filter has
+  /// moved on to another path since they were requested.
+  pub fn update_touched(&mut self, id: &str, path: &str, touched: HashSet<String>) {
+    if self.filter.path.as_deref() == Some(path) {
+      self.touched.insert(id.to_string(), touched);
+      self.clear_rows();
+      self.refresh_selection();
+    }
+  }
+
+  /// Opens the diff of the selected log with its repo's difftool, from the
+  /// marked log when there is one, or else to the tip of the repo's branch.
+  pub fn open_difftool(&mut self, sender: &mpsc::Sender<Event>) {
+    let launch = self
+      .selected_range()
+      .and_then(|target| self.difftool_launch(target));
+    self.launch_difftool(launch, sender);
+    self.mark_selected_seen();
+  }
+
+  /// Opens the diff of only the changes made by the selected log.
+  pub fn open_commit_difftool(&mut self, sender: &mpsc::Sender<Event>) {
+    let target = self
+      .selected_row()
+      .map(|row| (row.repo, row.log, DiffRange::commit(row.log)));
+    let launch = self.difftool_launch(target);
+    self.launch_difftool(launch, sender);
+    self.mark_selected_seen();
+  }
+
+  fn difftool_launch(&self, target: Option<(&Repo, &Log, DiffRange)>) -> Result<Option<DifftoolLaunch>, String> {
+    let Some((repo, log, range)) = target else {
+      return Ok(None);
+    };
+    let launch = match self.difftool.for_repo(repo) {
+      Difftool::Builtin => DifftoolLaunch::View(DiffView::new(&self.root_path, repo, log, &range)),
+      difftool => {
+        let command = difftool
+          .command(&self.root_path, repo, &range)
+          .map_err(|err| err.to_string())?;
+        if repo.difftool_terminal {
+          DifftoolLaunch::Terminal(command)
+        } else {
+          DifftoolLaunch::Background(command)
+        }
+      }
+    };
+    Ok(Some(launch))
+  }
+
+  /// Terminal difftools are held until [`App::take_terminal_difftool`], and
+  /// others are launched in the background.
+  fn launch_difftool(&mut self, launch: Result<Option<DifftoolLaunch>, String>, sender: &mpsc::Sender<Event>) {
+    self.difftool_failure = None;
+    match launch {
+      Ok(None) => {}
+      Ok(Some(DifftoolLaunch::View(view))) => self.diff_view = Some(view),
+      Ok(Some(DifftoolLaunch::Terminal(command))) => self.terminal_difftool = Some(command),
+      Ok(Some(DifftoolLaunch::Background(command))) => difftool::launch(command, sender.clone()),
+      Err(reason) => self.difftool_complete(Err(reason)),
+    }
+  }
+
+  /// The range to diff for the selected log. A marked log must belong to the
+  /// same repo.
+  fn selected_range(&self) -> Result<Option<(&Repo, &Log, DiffRange)>, String> {
+    let Some(row) = self.selected_row() else {
+      return Ok(None);
+    };
+    let range = match &self.range_mark {
+      None => DiffRange::to_until(row.log, row.repo, self.until.as_ref()),
+      Some((id, sha)) => {
+        let marked = (id == row.id)
+          .then(|| self.find_log(id, sha))
+          .flatten()
+          .ok_or("the marked commit is not in the selected commit's repo")?;
+        DiffRange::between(marked, row.log)
+      }
+    };
+    Ok(Some((row.repo, row.log, range)))
+  }
+
+  fn find_log(&self, id: &str, full_sha: &str) -> Option<&Log> {
+    self
+      .repos
+      .get(id)?
+      .logs
+      .iter()
+      .flat_map(|log| std::iter::once(log).chain(&log.merged))
+      .find(|log| log.full_sha == full_sha)
+  }
+
+  /// Marks the selected log as one end of a range to diff, or clears the mark
+  /// when it is already marked.
+  pub fn toggle_range_mark(&mut self) {
+    let Some(row) = self.selected_row() else {
+      return;
+    };
+    let mark = (row.id.to_string(), row.log.full_sha.clone());
+    self.range_mark = if self.range_mark.as_ref() == Some(&mark) {
+      None
+    } else {
+      Some(mark)
+    };
+  }
+
+  /// Clears the range mark once its log is no longer visible.
+  fn refresh_range_mark(&mut self) {
+    let Some((id, sha)) = &self.range_mark else {
+      return;
+    };
+    let visible = self.log_rows().iter().any(|index| {
+      let row = self.log_row(*index);
+      row.id == id && &row.log.full_sha == sha
+    });
+    if !visible {
+      self.range_mark = None;
+    }
+  }
+
+  /// Opens the forge page of the selected commit in the default browser.
+  pub fn open_commit_page(&mut self, sender: &mpsc::Sender<Event>) {
+    if let Some((repo, log)) = self.selected_log() {
+      let url = Forge::for_repo(repo, None).map(|forge| forge.commit_url(&log.full_sha));
+      self.open_url(url, sender);
+      self.mark_selected_seen();
+    }
+  }
+
+  /// Opens the forge page of the selected repo's branch in the default browser.
+  pub fn open_branch_page(&mut self, sender: &mpsc::Sender<Event>) {
+    if let Some((_id, repo)) = self.selected_repo() {
+      let url = Forge::for_repo(repo, None).map(|forge| match &repo.branch {
+        Some(branch) => forge.branch_url(branch),
+        None => forge.home_url(),
+      });
+      self.open_url(url, sender);
+    }
+  }
+
+  fn open_url(&mut self, url: AppResult<String>, sender: &mpsc::Sender<Event>) {
+    self.difftool_failure = None;
+    match url {
+      Ok(url) => {
+        let mut command = std::process::Command::new(OPEN_COMMAND);
+        command.arg(url);
+        difftool::launch(command, sender.clone());
+      }
+      Err(err) => self.difftool_complete(Err(err.to_string())),
+    }
+  }
+
+  pub fn take_terminal_difftool(&mut self) -> Option<std::process::Command> {
+    self.terminal_difftool.take()
+  }
+
+  pub fn difftool_complete(&mut self, result: Result<(), String>) {
+    if let Err(reason) = result {
+      log::error!("difftool failed: {reason}");
+      self.difftool_failure = Some(reason);
+    }
+  }
+
+  /// Closes the builtin difftool when the selection has moved to another log.
+  fn refresh_diff_view(&mut self) {
+    let Some(view) = &self.diff_view else {
+      return;
+    };
+    let selected = self.selected_log().map(|(_repo, log)| &log.full_sha);
+    if selected != Some(&view.full_sha) {
+      self.diff_view = None;
+    }
+  }
+
+  pub fn scroll_diff_view(&mut self, lines: isize) {
+    if let Some(view) = &mut self.diff_view {
+      let max = view
+        .patch
+        .as_ref()
+        .map_or(0, |patch| patch.lines.len().saturating_sub(1));
+      view.scroll = view.scroll.saturating_add_signed(lines).min(max);
+    }
+  }
+
+  /// Scrolls the builtin difftool to the next or previous file or hunk.
+  pub fn jump_diff_view(&mut self, starts: fn(&Patch) -> Vec<usize>, forward: bool) {
+    if let Some(view) = &mut self.diff_view
+      && let Ok(patch) = &view.patch
+    {
+      let starts = starts(patch);
+      let next = if forward {
+        patch::next_start(&starts, view.scroll)
+      } else {
+        patch::previous_start(&starts, view.scroll)
+      };
+      if let Some(next) = next {
+        view.scroll = next;
+      }
+    }
+  }
+
+  pub fn scroll_detail(&mut self, lines: i16) {
+    if let Some(detail) = &mut self.detail {
+      detail.scroll = detail.scroll.saturating_add_signed(lines);
+    }
+  }
+
+  fn selected_detail(&self) -> Option<CommitDetail> {
+    let (repo, log) = self.selected_log()?;
+    let changes = repo
+      .path(&self.root_path)
+      .and_then(|path| git::changes(&path, &log.full_sha))
+      .map_err(|err| err.to_string());
+    Some(CommitDetail {
+      changes,
+      log: log.clone(),
+      scroll: 0,
+    })
+  }
+
+  /// The row selected in the Diff pane.
+  pub fn selected_row(&self) -> Option<LogRow<'_>> {
+    self.visible_log(self.selected_repo_state.selected()?)
+  }
+
+  /// The log selected in the Diff pane, along with the repo it belongs to.
+  pub fn selected_log(&self) -> Option<(&Repo, &Log)> {
+    self.selected_row().map(|row| (row.repo, row.log))
+  }
+
+  /// Logs shown in the Diff pane. When the timeline is toggled on, these are
+  /// the logs of every repo merged into a single stream, newest first. Merges
+  /// that have been expanded are followed by the commits they brought in.
+  /// Logs not matching the filter are left out.
+  pub fn visible_logs(&self) -> Vec<LogRow<'_>> {
+    let rows = self.log_rows();
+    rows.iter().map(|index| self.log_row(*index)).collect()
+  }
+
+  /// The row at `index` of the Diff pane.
+  pub fn visible_log(&self, index: usize) -> Option<LogRow<'_>> {
+    let row = *self.log_rows().get(index)?;
+    Some(self.log_row(row))
+  }
+
+  pub fn visible_log_count(&self) -> usize {
+    self.log_rows().len()
+  }
+
+  /// The rows of the Diff pane, built if the selected repo has changed since
+  /// they were last read.
+  fn log_rows(&self) -> Ref<'_, [RowIndex]> {
+    let key = match self.timeline {
+      true => None,
+      false => match self.selected_repo_index() {
+        Some(index) => Some(index),
+        None => return Ref::map(self.log_rows.borrow(), |_| &[][..]),
+      },
+    };
+    if !matches!(&*self.log_rows.borrow(), Some((built_for, _)) if *built_for == key) {
+      *self.log_rows.borrow_mut() = Some((key, self.build_rows(key)));
+    }
+    Ref::map(self.log_rows.borrow(), |rows| match rows {
+      Some((_, rows)) => rows.as_slice(),
+      None => &[],
+    })
+  }
+
+  /// The logs of the repo at `index`, or of every repo when `None`.
+  fn build_rows(&self, index: Option<usize>) -> Vec<RowIndex> {
+    let mut logs: Vec<(usize, usize, &str, &Repo, &Log)> = self
+      .repos
+      .iter()
+      .enumerate()
+      .filter(|(repo_index, _)| index.is_none_or(|index| index == *repo_index))
+      .flat_map(|(repo_index, (id, repo))| {
+        repo
+          .logs
+          .iter()
+          .enumerate()
+          .map(move |(log_index, log)| (repo_index, log_index, id.as_str(), repo, log))
+      })
+      .collect();
+    if index.is_none() {
+      logs.sort_by_key(|(_, _, _, _, log)| *log);
+    }
+
+    let mut rows = Vec::with_capacity(logs.len());
+    for (repo_index, log_index, id, repo, log) in logs {
+      let touched = self.touched.get(id);
+      if !self.filter.matches(repo, log, touched) {
+        continue;
+      }
+      rows.push(RowIndex {
+        repo: repo_index,
+        log: log_index,
+        merged: None,
+      });
+      if self.expanded.contains(&(id.to_string(), log.sha.clone())) {
+        rows.extend(
+          log
+            .merged
+            .iter()
+            .enumerate()
+            .filter(|(_, merged)| self.filter.matches(repo, merged, touched))
+            .map(|(merged_index, _)| RowIndex {
+              repo: repo_index,
+              log: log_index,
+              merged: Some(merged_index),
+            }),
+        );
+      }
+    }
+    rows
+  }
+
+  fn log_row(&self, index: RowIndex) -> LogRow<'_> {
+    let (id, repo) = self
+      .repos
+      .get_index(index.repo)
+      .expect("rows are cleared when repos change");
+    let log = &repo.logs[index.log];
+    match index.merged {
+      Some(merged) => LogRow {
+        id,
+        repo,
+        log: &log.merged[merged],
+        nested: true,
+      },
+      None => LogRow {
+        id,
+        repo,
+        log,
+        nested: false,
+      },
+    }
+  }
+
+  /// Rebuilds the rows of the Diff pane the next time they are read.
+  fn clear_rows(&self) {
+    self.log_rows.take();
+  }
+
+  /// Rebuilds the rows of the Repos pane, and so of the Diff pane, the next
+  /// time they are read.
+  fn clear_repo_rows(&self) {
+    self.repo_row_indices.take();
+    self.clear_rows();
+  }
+
+  /// Expands or collapses the commits brought in by the selected merge.
+  pub fn toggle_selected_merge(&mut self) {
+    let Some(index) = self.selected_repo_state.selected() else {
+      return;
+    };
+    let key = match self.visible_log(index) {
+      Some(row) if !row.nested && !row.log.merged.is_empty() => (row.id.to_string(), row.log.sha.clone()),
+      _ => return,
+    };
+    if !self.expanded.remove(&key) {
+      self.expanded.insert(key);
+    }
+    self.clear_rows();
+    self.refresh_selection();
+  }
+
+  pub fn toggle_timeline(&mut self) {
+    self.timeline = !self.timeline;
+    self.clear_rows();
+    self.selected_repo_state.select(Some(0));
+    self.refresh_selection();
+  }
+
   pub fn reset(&mut self) {
     self.state = AppState::Init;
   }
 
   pub fn update(&self, sender: mpsc::Sender<Event>) -> AppResult<()> {
     for (id, repo) in &self.repos {
-      repo.update(id, &self.root_path, sender.clone(), Arc::clone(&self.semaphore))?;
+      let limits = repo.log_limits(&self.since, self.margin, self.max_commits);
+      repo.update(id, &self.root_path, limits, sender.clone(), Arc::clone(&self.semaphore))?;
     }
     Ok(())
   }
@@ -151,19 +1033,323 @@ impl App {
     Ok(())
   }
 
-  pub fn update_repo_logs(&mut self, id: &str, logs: Vec<Log>) -> AppResult<()> {
+  pub fn update_repo_logs(&mut self, id: &str, history: History, sender: &mpsc::Sender<Event>) -> AppResult<()> {
     if let Some(repo) = self.repos.get_mut(id) {
-      repo.logs = logs;
+      repo.logs = history.logs;
+      repo.truncated = history.truncated;
       repo.status = RepoStatus::Finished;
+      self
+        .filter
+        .spawn_touched(id, &self.root_path, repo, sender.clone());
+      let limits = repo.log_limits(&self.since, self.margin, self.max_commits);
+      self
+        .seen
+        .spawn_read(id, &self.root_path, repo, limits, sender.clone());
     }
-
-    self.repos.sort_unstable_by(&Self::sort_repos);
+    self.sort_repos();
 
     Ok(())
   }
 
-  #[allow(clippy::ptr_arg)]
-  fn sort_repos(_key1: &String, repo1: &Repo, _key2: &String, repo2: &Repo) -> Ordering {
-    repo1.cmp(repo2)
+  /// Switches to the next way of sorting repos.
+  pub fn cycle_sort(&mut self) {
+    self.sort = self.sort.next();
+    self.sort_repos();
+  }
+
+  /// Sorts the repos by `sort`, keeping the same repo or group selected.
+  fn sort_repos(&mut self) {
+    let selected = self.repo_state.selected().and_then(|index| {
+      let row = self.repo_row_indices().get(index).cloned()?;
+      Some(self.repo_row(&row).key())
+    });
+    self.clear_repo_rows();
+
+    match self.sort {
+      RepoSort::Manifest => {
+        let order = &self.manifest_order;
+        self
+          .repos
+          .sort_by_cached_key(|id, _repo| order.iter().position(|other| other == id))
+      }
+      sort => self
+        .repos
+        .sort_by(|_id1, repo1, _id2, repo2| sort.compare(repo1, repo2)),
+    }
+
+    let rows = self.repo_rows();
+    let index = selected
+      .and_then(|key| rows.iter().position(|row| row.key() == key))
+      .or_else(|| {
+        rows
+          .iter()
+          .position(|row| matches!(row, RepoRow::Repo { .. }))
+      });
+    self.repo_state.select(index);
+    self.refresh_selection();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::manifest::Remote;
+  use crate::theme::Theme;
+
+  fn log(sha: &str, seconds: i64) -> Log {
+    Log {
+      author: "Alice".into(),
+      commit_datetime: chrono::DateTime::from_timestamp(seconds, 0).unwrap(),
+      full_sha: format!("{sha}0000"),
+      message: sha.to_uppercase(),
+      sha: sha.into(),
+      ..Default::default()
+    }
+  }
+
+  fn app() -> App {
+    let mut manifest = Manifest {
+      root: Some(PathBuf::from("/tmp")),
+      since_datetime: Some(chrono::DateTime::from_timestamp(0, 0).unwrap()),
+      ..Default::default()
+    };
+    for id in ["one", "two"] {
+      manifest.remotes.insert(
+        id.into(),
+        Remote {
+          name: id.into(),
+          origin: format!("git@github.com:synchronal/{id}"),
+          ..Default::default()
+        },
+      );
+    }
+    let mut app = App::from_manifest(manifest, Seen::default(), Theme::Dark.try_into().unwrap());
+    app.repos.get_mut("one").unwrap().logs = vec![log("c", 300), log("a", 100)];
+    app.repos.get_mut("two").unwrap().logs = vec![log("b", 200)];
+    app
+  }
+
+  fn visible_shas(app: &App) -> Vec<&str> {
+    app
+      .visible_logs()
+      .iter()
+      .map(|row| row.log.sha.as_str())
+      .collect()
+  }
+
+  #[test]
+  fn activity_counts_commits_per_day() {
+    let mut app = app();
+    let day = chrono::DateTime::UNIX_EPOCH
+      .with_timezone(&Local)
+      .date_naive();
+    assert_eq!(app.activity(), HashMap::from([(day, 3)]));
+
+    app.activity_all_repos = false;
+    let index = app.repos.get_index_of("two").unwrap();
+    app.repo_state.select(Some(index));
+    assert_eq!(app.activity(), HashMap::from([(day, 1)]));
+  }
+
+  #[test]
+  fn visible_logs_for_selected_repo() {
+    let mut app = app();
+    let index = app.repos.get_index_of("two").unwrap();
+    app.repo_state.select(Some(index));
+
+    let shas = visible_shas(&app);
+    assert_eq!(shas, vec!["b"]);
+  }
+
+  #[test]
+  fn visible_logs_for_timeline_merges_repos() {
+    let mut app = app();
+    app.toggle_timeline();
+
+    let logs: Vec<(&str, &str)> = app
+      .visible_logs()
+      .iter()
+      .map(|row| (row.id, row.log.sha.as_str()))
+      .collect();
+    assert_eq!(logs, vec![("one", "c"), ("two", "b"), ("one", "a")]);
+
+    app.selected_repo_state.select(Some(1));
+    let (repo, log) = app.selected_log().unwrap();
+    assert_eq!((repo.name.as_str(), log.sha.as_str()), ("two", "b"));
+  }
+
+  #[test]
+  fn toggle_selected_merge_shows_merged_logs() {
+    let mut app = app();
+    let index = app.repos.get_index_of("one").unwrap();
+    app.repo_state.select(Some(index));
+    app.repos.get_mut("one").unwrap().logs[0].merged = vec![log("m2", 250), log("m1", 150)];
+
+    app.toggle_selected_merge();
+    let rows: Vec<(&str, bool)> = app
+      .visible_logs()
+      .iter()
+      .map(|row| (row.log.sha.as_str(), row.nested))
+      .collect();
+    assert_eq!(rows, vec![("c", false), ("m2", true), ("m1", true), ("a", false)]);
+
+    app.toggle_selected_merge();
+    assert_eq!(app.visible_logs().len(), 2);
+  }
+
+  #[test]
+  fn repo_rows_grouped_and_collapsed() {
+    let mut app = app();
+    app.sort = RepoSort::Group;
+    app.repos.get_mut("two").unwrap().group = Some("backend".into());
+
+    let rows: Vec<String> = app
+      .repo_rows()
+      .iter()
+      .map(|row| match row {
+        RepoRow::Group { name, repos, .. } => format!("{name} ({})", repos.len()),
+        RepoRow::Repo { id, grouped, .. } => format!("{id} {grouped}"),
+      })
+      .collect();
+    assert_eq!(rows, vec!["backend (1)", "two true", "one false"]);
+
+    app.repo_state.select(Some(0));
+    assert!(app.selected_repo().is_none());
+    app.toggle_selected_group();
+    assert_eq!(app.repo_rows().len(), 2);
+    app.repo_state.select(Some(1));
+    assert_eq!(app.selected_repo().map(|(id, _repo)| id.as_str()), Some("one"));
+  }
+
+  #[test]
+  fn sort_repos_keeps_selected_repo() {
+    let mut app = app();
+    app.repos.get_mut("one").unwrap().name = "zed".into();
+    let index = app.repos.get_index_of("two").unwrap();
+    app.repo_state.select(Some(index));
+
+    let ids = |app: &App| app.repos.keys().cloned().collect::<Vec<_>>();
+    app
+      .update_repo_logs("two", History::default(), &mpsc::channel().0)
+      .unwrap();
+    assert_eq!(ids(&app), vec!["one", "two"]);
+    assert_eq!(app.selected_repo().unwrap().0, "two");
+
+    app.cycle_sort();
+    assert_eq!(app.sort, RepoSort::Name);
+    assert_eq!(ids(&app), vec!["two", "one"]);
+    assert_eq!(app.selected_repo().unwrap().0, "two");
+
+    app.cycle_sort();
+    assert_eq!(app.sort, RepoSort::Manifest);
+    assert_eq!(ids(&app), app.manifest_order);
+    assert_eq!(app.selected_repo().unwrap().0, "two");
+  }
+
+  #[test]
+  fn visible_logs_rebuilt_when_logs_change() {
+    let mut app = app();
+    app.toggle_timeline();
+    assert_eq!(app.visible_logs().len(), 3);
+
+    let history = History {
+      logs: vec![log("d", 400), log("b", 200)],
+      ..Default::default()
+    };
+    app
+      .update_repo_logs("two", history, &mpsc::channel().0)
+      .unwrap();
+    let shas = visible_shas(&app);
+    assert_eq!(shas, vec!["d", "c", "b", "a"]);
+  }
+
+  #[test]
+  fn visible_logs_match_filter() {
+    let mut app = app();
+    app.toggle_timeline();
+    app.repos.get_mut("one").unwrap().logs[1].author = "dependabot[bot]".into();
+
+    app.search = "^[AB]".into();
+    app.apply_search(&mpsc::channel().0);
+    let shas = visible_shas(&app);
+    assert_eq!(shas, vec!["b", "a"]);
+
+    app.repos.get_mut("one").unwrap().exclude_authors = vec!["dependabot[bot]".into()];
+    app.clear_rows();
+    let shas = visible_shas(&app);
+    assert_eq!(shas, vec!["b"]);
+
+    app.search = "fix(".into();
+    app.apply_search(&mpsc::channel().0);
+    assert!(app.search_error.is_some());
+    assert_eq!(app.filter.to_string(), "^[AB]");
+  }
+
+  #[test]
+  fn update_touched_ignores_stale_paths() {
+    let mut app = app();
+    app.toggle_timeline();
+    app.search = "path:src".into();
+    app.apply_search(&mpsc::channel().0);
+    assert!(app.visible_logs().is_empty());
+
+    app.update_touched("one", "lib", HashSet::from(["a0000".to_string()]));
+    assert!(app.visible_logs().is_empty());
+
+    app.update_touched("one", "src", HashSet::from(["a0000".to_string()]));
+    let shas = visible_shas(&app);
+    assert_eq!(shas, vec!["a"]);
+  }
+
+  #[test]
+  fn mark_seen_counts_unread_logs() {
+    let mut app = app();
+    let index = app.repos.get_index_of("one").unwrap();
+    app.repo_state.select(Some(index));
+    assert_eq!(app.unread_count("one", &app.repos["one"]), 2);
+
+    app.selected_repo_state.select(Some(1));
+    app.mark_selected_seen();
+    assert_eq!(app.unread_count("one", &app.repos["one"]), 1);
+
+    app.mark_all_seen();
+    assert_eq!(app.unread_count("one", &app.repos["one"]), 0);
+    assert_eq!(app.unread_count("two", &app.repos["two"]), 0);
+  }
+
+  #[test]
+  fn range_mark_diffs_from_marked_to_selected_log() {
+    let mut app = app();
+    let index = app.repos.get_index_of("one").unwrap();
+    app.repo_state.select(Some(index));
+    app.repos.get_mut("one").unwrap().logs[1].parents = vec!["z".into()];
+
+    app.toggle_range_mark();
+    app.selected_repo_state.select(Some(1));
+    let (_repo, log, range) = app.selected_range().unwrap().unwrap();
+    assert_eq!(log.sha, "a");
+    assert_eq!(
+      range,
+      DiffRange {
+        from: "z".into(),
+        to: Some("c0000".into()),
+      }
+    );
+
+    app.selected_repo_state.select(Some(0));
+    app.toggle_range_mark();
+    assert_eq!(app.range_mark, None);
+  }
+
+  #[test]
+  fn range_mark_must_be_in_the_selected_repo() {
+    let mut app = app();
+    app.toggle_timeline();
+    app.toggle_range_mark();
+    app.selected_repo_state.select(Some(1));
+
+    let rows: Vec<&str> = app.visible_logs().iter().map(|row| row.id).collect();
+    assert_eq!(rows, vec!["one", "two", "one"]);
+    assert!(app.selected_range().is_err());
   }
 }
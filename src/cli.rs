@@ -1,3 +1,4 @@
+use crate::output;
 use crate::theme;
 use clap::Args;
 use clap::Parser;
@@ -23,8 +24,49 @@ pub enum Command {
   },
   /// Open the daily diff. Defaults to (-m ./dyd.toml).
   Diff(ManifestCliArgs),
+  /// Clone or pull each repo into the local cache, without opening the UI,
+  /// ie from cron. Exits nonzero when any repo fails to update.
+  Fetch(FetchCliArgs),
   /// Generate a (toml-encoded) manifest for defining repos to diff.
   Init(ManifestCliArgs),
+  /// Print the logs of each repo to stdout, without opening the UI.
+  Log(LogCliArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct FetchCliArgs {
+  #[clap(flatten)]
+  pub manifest_args: ManifestCliArgs,
+
+  /// Only fetch the remote with this id. May be given more than once.
+  #[arg(long, value_name = "ID")]
+  pub only: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct LogCliArgs {
+  #[clap(flatten)]
+  pub manifest_args: ManifestCliArgs,
+
+  /// Output format
+  #[arg(short, long, value_enum, default_value_t = output::Format::Text)]
+  pub format: output::Format,
+
+  /// Read logs from the local cache, without fetching from remotes.
+  #[arg(long, action)]
+  pub no_fetch: bool,
+
+  /// Only print logs whose author's name contains this, ignoring case.
+  #[arg(long)]
+  pub author: Option<String>,
+
+  /// Only print logs whose message matches this regex.
+  #[arg(long)]
+  pub grep: Option<String>,
+
+  /// Only print logs that change this file, or a file in this directory.
+  #[arg(long, value_hint = clap::ValueHint::AnyPath)]
+  pub path: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -37,6 +79,20 @@ pub struct ManifestCliArgs {
   /// Color theme
   #[arg(short, long, env = "DYD_THEME", value_hint = clap::ValueHint::FilePath)]
   pub theme: Option<theme::Theme>,
+
+  /// Show logs from this time, overriding the manifest's `since`, ie
+  /// `yesterday`, `last friday`, `2026-10-01` or `3 business days ago`.
+  #[arg(long)]
+  pub since: Option<String>,
+
+  /// Dim logs from this time on, overriding the manifest's `until`, in the
+  /// same forms as `--since`. Named days are included in full.
+  #[arg(long)]
+  pub until: Option<String>,
+
+  /// Only open remotes with this tag. May be given more than once.
+  #[arg(long = "tag", value_name = "TAG")]
+  pub tags: Vec<String>,
 }
 
 impl CLI {
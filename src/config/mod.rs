@@ -1,12 +1,16 @@
 use crate::theme;
 use anyhow::Result;
-use serde::Deserialize;
+use chrono::Weekday;
+use serde::{Deserialize, Deserializer};
 use std::fs;
 use std::path::PathBuf;
 
 #[derive(Debug, Deserialize, Default)]
 pub struct Config {
   pub theme: Option<theme::Theme>,
+  /// The day that weeks start on in the calendar, ie `monday`.
+  #[serde(default, deserialize_with = "deserialize_weekday")]
+  pub first_weekday: Option<Weekday>,
 }
 
 impl Config {
@@ -23,7 +27,34 @@ impl Config {
   }
 }
 
+fn deserialize_weekday<'de, D>(deserializer: D) -> Result<Option<Weekday>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  match Option::<String>::deserialize(deserializer)? {
+    Some(weekday) => weekday
+      .parse()
+      .map(Some)
+      .map_err(|_| serde::de::Error::custom(format!("unknown weekday `{weekday}`"))),
+    None => Ok(None),
+  }
+}
+
 fn config_path() -> Result<PathBuf> {
   let home = std::env::var("HOME")?;
   Ok(PathBuf::from(home).join(".config/dyd/dyd.toml"))
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn config_first_weekday() {
+    let config: Config = toml::from_str("first_weekday = \"monday\"").unwrap();
+    assert_eq!(config.first_weekday, Some(Weekday::Mon));
+
+    let error = toml::from_str::<Config>("first_weekday = \"someday\"").unwrap_err();
+    assert!(error.to_string().contains("unknown weekday `someday`"));
+  }
+}
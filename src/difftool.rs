@@ -1,101 +1,228 @@
+use crate::app::{AppResult, Event};
+use crate::forge::{Forge, ForgeKind, OPEN_COMMAND};
 use crate::git::repo::{Log, Repo};
-use regex::Regex;
 use serde::Deserialize;
 use serde::de::{Deserializer, IntoDeserializer, value};
+use std::collections::HashMap;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, ExitStatus, Stdio};
 use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub enum Difftool {
+  /// Shows the diff in dyd itself, rather than in an external program.
+  Builtin,
+  /// Opens the remote's forge compare page in the default browser.
+  Browser,
   #[default]
   Git,
+  /// Like [`Difftool::Browser`], assuming GitHub when the forge can not be
+  /// detected.
   GitHub,
   Fallthrough(String),
 }
 
+/// Runs a difftool `command` on a background thread, so that the UI keeps
+/// drawing while it is open. Its outcome is sent as [`Event::DifftoolComplete`].
+pub fn launch(mut command: Command, sender: mpsc::Sender<Event>) {
+  thread::spawn(move || {
+    let result = command
+      .stdin(Stdio::null())
+      .output()
+      .map_err(|err| format!("Unable to run {:?}: {err}", command.get_program()))
+      .and_then(|output| outcome(output.status, &output.stderr));
+    if let Err(err) = sender.send(Event::DifftoolComplete(result)) {
+      log::error!("failed sending difftool outcome: {err}");
+    }
+  });
+}
+
+/// Runs a difftool `command` that draws in the terminal, waiting for it to exit.
+pub fn run_in_terminal(mut command: Command) -> Result<(), String> {
+  command
+    .status()
+    .map_err(|err| format!("Unable to run {:?}: {err}", command.get_program()))
+    .and_then(|status| outcome(status, &[]))
+}
+
+fn outcome(status: ExitStatus, stderr: &[u8]) -> Result<(), String> {
+  if status.success() {
+    return Ok(());
+  }
+  let stderr = String::from_utf8_lossy(stderr);
+  match stderr.trim() {
+    "" => Err(format!("Difftool {status}")),
+    stderr => Err(stderr.to_string()),
+  }
+}
+
+/// Splits a difftool `template` into words with POSIX shell quoting rules,
+/// then interpolates `${VAR}`s from `context` into each word, so that values
+/// containing spaces remain single arguments.
+pub fn argv(template: &str, context: &HashMap<String, String>) -> AppResult<Vec<String>> {
+  shell_words::split(template)?
+    .iter()
+    .map(|word| Ok(envsubst::substitute(word, context)?))
+    .collect()
+}
+
+/// The id of git's empty tree, which a range from a root commit starts at.
+pub const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// The commits to diff between.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiffRange {
+  pub from: String,
+  /// The later commit, or the tip of the repo's branch when `None`.
+  pub to: Option<String>,
+}
+
+impl DiffRange {
+  /// From `log` to the tip of the repo's branch.
+  pub fn to_tip(log: &Log) -> Self {
+    DiffRange {
+      from: log.ref_from().to_string(),
+      to: None,
+    }
+  }
+
+  /// From `log` to the newest log of `repo` older than `until`, or to the
+  /// tip when there is no `until` or `log` is itself newer.
+  pub fn to_until(log: &Log, repo: &Repo, until: Option<&chrono::DateTime<chrono::Utc>>) -> Self {
+    DiffRange {
+      to: until
+        .filter(|until| log.commit_datetime < **until)
+        .and_then(|until| repo.newest_until(until))
+        .map(|newest| newest.full_sha.clone()),
+      ..Self::to_tip(log)
+    }
+  }
+
+  /// Only the changes made by `log`, from its first parent.
+  pub fn commit(log: &Log) -> Self {
+    Self::between(log, log)
+  }
+
+  /// From the older of `a` and `b` to the newer, including the changes made
+  /// by both. Starts at the empty tree when the older is a root commit.
+  pub fn between(a: &Log, b: &Log) -> Self {
+    let (newer, older) = if a <= b { (a, b) } else { (b, a) };
+    DiffRange {
+      from: older
+        .parents
+        .first()
+        .cloned()
+        .unwrap_or_else(|| EMPTY_TREE.to_string()),
+      to: Some(newer.full_sha.clone()),
+    }
+  }
+
+  /// The ref to diff to in the cached clone.
+  pub fn ref_to(&self, repo: &Repo) -> String {
+    self.to.clone().unwrap_or_else(|| ref_to(repo))
+  }
+
+  /// The ref to diff to on the repo's forge.
+  fn forge_ref_to(&self, repo: &Repo) -> String {
+    self
+      .to
+      .clone()
+      .or_else(|| repo.branch.clone())
+      .unwrap_or("HEAD".to_owned())
+  }
+}
+
+/// The ref to diff to: the tip of the repo's branch, or `HEAD`.
+pub fn ref_to(repo: &Repo) -> String {
+  match repo.branch.clone() {
+    Some(branch) => format!("origin/{branch}"),
+    None => "HEAD".into(),
+  }
+}
+
 impl Difftool {
-  pub fn open(&self, root_path: &Path, repo: &Repo, log: &Log) {
-    let mut cmd: String = "".to_string();
-    let mut args: Vec<String> = vec![];
-    let ref_to: String = match repo.branch.clone() {
-      Some(branch) => format!("origin/{branch}"),
-      None => "HEAD".into(),
-    };
-    let diff = format!("{}..{ref_to}", log.sha);
-    let repo_path = repo.path(root_path).unwrap();
-
-    let cwd = std::env::current_dir()
-      .unwrap()
+  /// The difftool to use for `repo`, which may override this one.
+  pub fn for_repo<'a>(&'a self, repo: &'a Repo) -> &'a Difftool {
+    repo.difftool.as_ref().unwrap_or(self)
+  }
+
+  /// Builds the command that opens the diff from `log` in `repo`.
+  pub fn command(&self, root_path: &Path, repo: &Repo, range: &DiffRange) -> AppResult<Command> {
+    let ref_to = range.ref_to(repo);
+    let ref_from = range.from.as_str();
+    let diff = format!("{ref_from}..{ref_to}");
+    let repo_path = repo.path(root_path)?;
+
+    let cwd = std::env::current_dir()?
       .into_os_string()
       .into_string()
-      .unwrap();
+      .map_err(|cwd| format!("Working directory is not valid unicode: {cwd:?}"))?;
 
-    let mut context = std::collections::HashMap::new();
+    let mut context = HashMap::new();
     context.insert("DYD_PWD".to_string(), cwd.clone());
     context.insert("DIFF".to_string(), diff.clone());
     context.insert("ORIGIN".to_string(), repo.origin.clone());
-    context.insert("REF_FROM".to_string(), log.sha.clone());
+    context.insert("REF_FROM".to_string(), ref_from.to_string());
     context.insert("REF_TO".to_string(), ref_to.clone());
     assert!(envsubst::validate_vars(&context).is_ok());
 
-    let difftool_expansion = envsubst::substitute(self.command_template(repo, &log.sha), &context).unwrap();
-
-    let difftool_parts: Vec<&str> = difftool_expansion.split(' ').collect();
-    difftool_parts
-      .iter()
-      .enumerate()
-      .for_each(|(index, value)| {
-        if index == 0 {
-          cmd = value.to_string();
-        } else {
-          args.push(value.to_string());
-        }
-      });
-
-    match Command::new(cmd)
-      .args(args)
+    let template = self.for_repo(repo).command_template(repo, range)?;
+    let mut command = if repo.difftool_shell {
+      // The shell expands `${VAR}` itself, from the environment set below.
+      let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".into());
+      let mut command = Command::new(shell);
+      command.arg("-c").arg(template);
+      command
+    } else {
+      let mut argv = argv(&template, &context)?.into_iter();
+      let cmd = argv.next().ok_or("Difftool command is empty")?;
+      let mut command = Command::new(cmd);
+      command.args(argv);
+      command
+    };
+    command
       .env("DYD_PWD", cwd)
       .env("DIFF", diff)
-      .env("REF_FROM", &log.sha)
+      .env("REF_FROM", ref_from)
       .env("REF_TO", ref_to)
       .env("ORIGIN", &repo.origin)
-      .current_dir(repo_path)
-      .output()
-    {
-      Ok(_) => (),
-      Err(err) => eprintln!("\rError opening difftool:\r\n{err:?}\r\ndifftool: {self}"),
-    };
+      .current_dir(repo_path);
+    Ok(command)
   }
 
-  pub fn command_template(&self, repo: &Repo, from_sha: &str) -> String {
+  /// The command that an external difftool runs. The builtin difftool is
+  /// shown in dyd itself, so has none.
+  pub fn command_template(&self, repo: &Repo, range: &DiffRange) -> AppResult<String> {
     match self {
-      Difftool::Git => "git difftool -g -y ${DIFF}".to_owned(),
-      Difftool::GitHub => Difftool::github_diff_url(repo, from_sha),
-      Difftool::Fallthrough(difftool) => difftool.clone(),
+      Difftool::Builtin => Err("The builtin difftool does not run a command".into()),
+      Difftool::Browser => Difftool::forge_diff_url(repo, range, None),
+      Difftool::Git => Ok("git difftool -g -y ${DIFF}".to_owned()),
+      Difftool::GitHub => Difftool::forge_diff_url(repo, range, Some(ForgeKind::GitHub)),
+      Difftool::Fallthrough(difftool) => Ok(difftool.clone()),
     }
   }
 
-  fn github_diff_url(repo: &Repo, from_sha: &str) -> String {
-    let origin = repo.origin.clone();
-
-    let trailing_git_re = Regex::new(r"\.git$").unwrap();
-    let origin = trailing_git_re.replace_all(&origin, "");
-
-    let origin_re = Regex::new(r"^([^@]+@|[^:]+://)([^:]+)[:/](.+)$").unwrap();
-    let caps = origin_re.captures(&origin).unwrap();
-    let url = caps.get(2).unwrap().as_str();
-    let repository = caps.get(3).unwrap().as_str();
-
-    let github_url = format!("https://{url}/{repository}");
-    let ref_to = repo.branch.clone().unwrap_or("HEAD".to_owned());
-    format!("open {github_url}/compare/{from_sha}..{ref_to}?diff=split")
+  fn forge_diff_url(repo: &Repo, range: &DiffRange, default: Option<ForgeKind>) -> AppResult<String> {
+    let forge = Forge::for_repo(repo, default)?;
+    let ref_to = range.forge_ref_to(repo);
+    // Forges can not compare from the empty tree, so a range from a root
+    // commit opens the page of its newest commit instead.
+    let url = if range.from == EMPTY_TREE {
+      forge.commit_url(&ref_to)
+    } else {
+      forge.compare_url(&range.from, &ref_to)
+    };
+    Ok(format!("{OPEN_COMMAND} {url}"))
   }
 }
 
 impl std::fmt::Display for Difftool {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
+      Difftool::Builtin => write!(f, "builtin"),
+      Difftool::Browser => write!(f, "browser"),
       Difftool::Git => write!(f, "git"),
       Difftool::GitHub => write!(f, "github"),
       Difftool::Fallthrough(difftool) => write!(f, "fallthrough: {difftool}"),
@@ -118,7 +245,11 @@ impl<'de> Deserialize<'de> for Difftool {
   {
     let s = String::deserialize(deserializer)?;
 
-    let deserialized = if s == *"git" {
+    let deserialized = if s == *"builtin" {
+      Self::Builtin
+    } else if s == *"browser" {
+      Self::Browser
+    } else if s == *"git" {
       Self::Git
     } else if s == *"github" {
       Self::GitHub
@@ -131,6 +262,8 @@ impl<'de> Deserialize<'de> for Difftool {
 
 #[cfg(test)]
 mod tests {
+  use crate::forge::OPEN_COMMAND;
+
   #[test]
   fn difftool_git_command_template() {
     let difftool = super::Difftool::Git;
@@ -139,10 +272,37 @@ mod tests {
       origin: "git@github.com:synchronal/dyd.git".into(),
       ..Default::default()
     };
-    let from_sha = "abc1234";
+    let range = super::DiffRange {
+      from: "abc1234".into(),
+      to: None,
+    };
 
-    let string = difftool.command_template(&repo, &from_sha);
-    assert_eq!(string, "git difftool -g -y ${DIFF}")
+    let string = difftool.command_template(&repo, &range).unwrap();
+    assert_eq!(string, "git difftool -g -y ${DIFF}");
+    assert!(
+      super::Difftool::Builtin
+        .command_template(&repo, &range)
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn difftool_github_command_template_from_root_commit() {
+    let difftool = super::Difftool::GitHub;
+    let repo = crate::git::repo::Repo {
+      origin: "git@github.com:synchronal/dyd.git".into(),
+      ..Default::default()
+    };
+    let range = super::DiffRange {
+      from: super::EMPTY_TREE.into(),
+      to: Some("abc1234".into()),
+    };
+
+    let string = difftool.command_template(&repo, &range).unwrap();
+    assert_eq!(
+      string,
+      format!("{OPEN_COMMAND} https://github.com/synchronal/dyd/commit/abc1234")
+    )
   }
 
   #[test]
@@ -153,12 +313,15 @@ mod tests {
       origin: "git@github.com:synchronal/dyd.git".into(),
       ..Default::default()
     };
-    let from_sha = "abc1234";
+    let range = super::DiffRange {
+      from: "abc1234".into(),
+      to: None,
+    };
 
-    let string = difftool.command_template(&repo, &from_sha);
+    let string = difftool.command_template(&repo, &range).unwrap();
     assert_eq!(
       string,
-      "open https://github.com/synchronal/dyd/compare/abc1234..HEAD?diff=split"
+      format!("{OPEN_COMMAND} https://github.com/synchronal/dyd/compare/abc1234..HEAD?diff=split")
     )
   }
 
@@ -171,12 +334,15 @@ mod tests {
       origin: "git@github.com:synchronal/dyd.git".into(),
       ..Default::default()
     };
-    let from_sha = "abc1234";
+    let range = super::DiffRange {
+      from: "abc1234".into(),
+      to: None,
+    };
 
-    let string = difftool.command_template(&repo, &from_sha);
+    let string = difftool.command_template(&repo, &range).unwrap();
     assert_eq!(
       string,
-      "open https://github.com/synchronal/dyd/compare/abc1234..my-branch?diff=split"
+      format!("{OPEN_COMMAND} https://github.com/synchronal/dyd/compare/abc1234..my-branch?diff=split")
     )
   }
 
@@ -189,12 +355,142 @@ mod tests {
       origin: "https://github.com/synchronal/dyd.git".into(),
       ..Default::default()
     };
-    let from_sha = "abc1234";
+    let range = super::DiffRange {
+      from: "abc1234".into(),
+      to: None,
+    };
 
-    let string = difftool.command_template(&repo, &from_sha);
+    let string = difftool.command_template(&repo, &range).unwrap();
     assert_eq!(
       string,
-      "open https://github.com/synchronal/dyd/compare/abc1234..my-branch?diff=split"
+      format!("{OPEN_COMMAND} https://github.com/synchronal/dyd/compare/abc1234..my-branch?diff=split")
     )
   }
+
+  #[test]
+  fn difftool_builtin_from_str() {
+    assert_eq!("builtin".parse::<super::Difftool>().unwrap(), super::Difftool::Builtin);
+  }
+
+  #[test]
+  fn difftool_outcome_reports_stderr() {
+    use std::os::unix::process::ExitStatusExt;
+
+    assert_eq!(super::outcome(std::process::ExitStatus::from_raw(0), b""), Ok(()));
+    assert_eq!(
+      super::outcome(std::process::ExitStatus::from_raw(256), b"fatal: bad revision\n"),
+      Err("fatal: bad revision".to_string())
+    );
+    assert_eq!(
+      super::outcome(std::process::ExitStatus::from_raw(256), b""),
+      Err("Difftool exit status: 1".to_string())
+    );
+  }
+
+  #[test]
+  fn difftool_argv_respects_quotes() {
+    let context = std::collections::HashMap::from([("DIFF".to_string(), "abc..HEAD".to_string())]);
+
+    assert_eq!(
+      super::argv(r#"my-tool  --title "two words" 'it''s' ${DIFF}"#, &context).unwrap(),
+      vec!["my-tool", "--title", "two words", "its", "abc..HEAD"]
+    );
+    assert_eq!(
+      super::argv(r#"sh -c "git diff ${DIFF} | less""#, &context).unwrap(),
+      vec!["sh", "-c", "git diff abc..HEAD | less"]
+    );
+  }
+
+  #[test]
+  fn difftool_argv_keeps_interpolated_values_whole() {
+    let context = std::collections::HashMap::from([("DYD_PWD".to_string(), "/tmp/with space".to_string())]);
+
+    assert_eq!(
+      super::argv("ls ${DYD_PWD}", &context).unwrap(),
+      vec!["ls", "/tmp/with space"]
+    );
+  }
+
+  #[test]
+  fn difftool_argv_rejects_unbalanced_quotes() {
+    assert!(super::argv(r#"my-tool "oops"#, &std::collections::HashMap::new()).is_err());
+  }
+
+  #[test]
+  fn difftool_browser_gitlab_command_template() {
+    let difftool = super::Difftool::Browser;
+    let repo = crate::git::repo::Repo {
+      branch: Some("main".into()),
+      forge: Some(crate::forge::ForgeKind::GitLab),
+      forge_url: Some("https://git.example.com".into()),
+      name: "test repo".into(),
+      origin: "git@git.example.com:team/app.git".into(),
+      ..Default::default()
+    };
+
+    let range = super::DiffRange {
+      from: "abc1234".into(),
+      to: None,
+    };
+
+    let string = difftool.command_template(&repo, &range).unwrap();
+    assert_eq!(
+      string,
+      format!("{OPEN_COMMAND} https://git.example.com/team/app/-/compare/abc1234...main")
+    )
+  }
+
+  #[test]
+  fn diff_range_to_until_stops_at_newest_log_in_window() {
+    let at = |seconds| chrono::DateTime::from_timestamp(seconds, 0).unwrap();
+    let log = |sha: &str, seconds| crate::git::repo::Log {
+      commit_datetime: at(seconds),
+      full_sha: format!("{sha}0000"),
+      sha: sha.into(),
+      ..Default::default()
+    };
+    let repo = crate::git::repo::Repo {
+      logs: vec![log("ghi", 300), log("def", 200), log("abc", 100)],
+      ..Default::default()
+    };
+
+    let range = super::DiffRange::to_until(&repo.logs[2], &repo, Some(&at(250)));
+    assert_eq!(range.from, "abc0000");
+    assert_eq!(range.to.as_deref(), Some("def0000"));
+    assert_eq!(super::DiffRange::to_until(&repo.logs[2], &repo, None).to, None);
+    assert_eq!(
+      super::DiffRange::to_until(&repo.logs[0], &repo, Some(&at(250))).to,
+      None
+    );
+  }
+
+  #[test]
+  fn diff_range_between_includes_both_logs() {
+    let log = |sha: &str, parents: &[&str], seconds| crate::git::repo::Log {
+      commit_datetime: chrono::DateTime::from_timestamp(seconds, 0).unwrap(),
+      full_sha: sha.into(),
+      parents: parents.iter().map(|parent| parent.to_string()).collect(),
+      sha: sha.into(),
+      ..Default::default()
+    };
+    let (older, newer) = (log("abc", &["aaa"], 100), log("def", &["abc"], 200));
+    let expected = super::DiffRange {
+      from: "aaa".into(),
+      to: Some("def".into()),
+    };
+
+    assert_eq!(super::DiffRange::between(&older, &newer), expected);
+    assert_eq!(super::DiffRange::between(&newer, &older), expected);
+    assert_eq!(
+      super::DiffRange::commit(&newer),
+      super::DiffRange {
+        from: "abc".into(),
+        to: Some("def".into()),
+      }
+    );
+
+    let root = log("000", &[], 50);
+    assert_eq!(super::DiffRange::commit(&root).from, super::EMPTY_TREE);
+    assert_eq!(super::DiffRange::between(&root, &newer).from, super::EMPTY_TREE);
+  }
 }
@@ -0,0 +1,272 @@
+//! Narrowing logs by author, message and the paths they touch.
+
+use crate::app::{AppResult, Event};
+use crate::git;
+use crate::git::repo::{Log, Repo};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+
+/// Failures parsing a search query.
+#[derive(Debug, Eq, PartialEq)]
+pub enum FilterError {
+  /// The query has unbalanced quotes.
+  Query(String),
+  /// The message pattern is not a valid regex.
+  Regex(String),
+}
+
+impl std::fmt::Display for FilterError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      FilterError::Query(reason) => write!(f, "invalid search: {reason}"),
+      FilterError::Regex(reason) => write!(f, "invalid message pattern: {reason}"),
+    }
+  }
+}
+impl std::error::Error for FilterError {}
+
+/// Logs to show. Every part that is set must match.
+#[derive(Clone, Debug, Default)]
+pub struct LogFilter {
+  /// Matched case-insensitively against part of the author's name.
+  pub author: Option<String>,
+  pub message: Option<Regex>,
+  /// A file or directory that the log must have changed.
+  pub path: Option<String>,
+}
+
+impl LogFilter {
+  pub fn new(author: Option<String>, message: Option<&str>, path: Option<String>) -> Result<LogFilter, FilterError> {
+    let message = message
+      .map(|pattern| Regex::new(pattern).map_err(|err| FilterError::Regex(err.to_string())))
+      .transpose()?;
+    Ok(LogFilter {
+      author,
+      message,
+      path: path.map(|path| path.trim_end_matches('/').to_string()),
+    })
+  }
+
+  /// Parses a search such as `author:alice path:src/ fix.*typo`. Words
+  /// without a prefix are joined into the message pattern, and prefixed
+  /// values may be quoted, ie `author:"Alice Smith"`.
+  pub fn parse(query: &str) -> Result<LogFilter, FilterError> {
+    let mut author = None;
+    let mut path = None;
+    let mut message = vec![];
+    let mut rest = query.trim_start();
+    while !rest.is_empty() {
+      let (word, remainder) = if let Some(value) = rest.strip_prefix("author:") {
+        let (value, remainder) = prefixed_value(value)?;
+        author = Some(value);
+        ("", remainder)
+      } else if let Some(value) = rest.strip_prefix("path:") {
+        let (value, remainder) = prefixed_value(value)?;
+        path = Some(value);
+        ("", remainder)
+      } else {
+        rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len()))
+      };
+      if !word.is_empty() {
+        message.push(word);
+      }
+      rest = remainder.trim_start();
+    }
+    let message = (!message.is_empty()).then(|| message.join(" "));
+    LogFilter::new(author, message.as_deref(), path)
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.author.is_none() && self.message.is_none() && self.path.is_none()
+  }
+
+  /// Whether `log` should be shown. `touched` holds the full shas of the
+  /// repo's logs that change `path`, and is only read when `path` is set.
+  pub fn matches(&self, repo: &Repo, log: &Log, touched: Option<&HashSet<String>>) -> bool {
+    if repo.excludes(log) {
+      return false;
+    }
+    if let Some(author) = &self.author
+      && !log.author.to_lowercase().contains(&author.to_lowercase())
+    {
+      return false;
+    }
+    if let Some(message) = &self.message
+      && !message.is_match(log.message.trim_end())
+      && !message.is_match(&log.body)
+    {
+      return false;
+    }
+    if self.path.is_some() && !touched.is_some_and(|touched| touched.contains(&log.full_sha)) {
+      return false;
+    }
+    true
+  }
+
+  /// The full shas of the logs of `repo`, including those brought in by
+  /// merges, that change `path`. Empty when no path is set.
+  pub fn touched(&self, root_path: &Path, repo: &Repo) -> AppResult<HashSet<String>> {
+    let Some(path) = &self.path else {
+      return Ok(HashSet::new());
+    };
+    touching(&repo.path(root_path)?, &shas(repo), path)
+  }
+
+  /// Like [`LogFilter::touched`], on a background thread so that the UI keeps
+  /// drawing while the trees are diffed. The shas are sent with the path they
+  /// were found for as [`Event::TouchedComplete`]. Does nothing when no path
+  /// is set.
+  pub fn spawn_touched(&self, id: &str, root_path: &Path, repo: &Repo, sender: mpsc::Sender<Event>) {
+    let Some(path) = self.path.clone() else {
+      return;
+    };
+    let repo_path = match repo.path(root_path) {
+      Ok(repo_path) => repo_path,
+      Err(err) => {
+        log::error!("failed filtering by path: {id}, reason: {err}");
+        return;
+      }
+    };
+    let shas = shas(repo);
+    let id = id.to_string();
+    thread::spawn(move || match touching(&repo_path, &shas, &path) {
+      Ok(touched) => {
+        if let Err(err) = sender.send(Event::TouchedComplete(id, path, touched)) {
+          log::error!("failed sending touched logs: {err}");
+        }
+      }
+      Err(err) => log::error!("failed filtering by path: {id}, reason: {err}"),
+    });
+  }
+}
+
+fn shas(repo: &Repo) -> Vec<String> {
+  repo
+    .logs
+    .iter()
+    .flat_map(|log| std::iter::once(log).chain(&log.merged))
+    .map(|log| log.full_sha.clone())
+    .collect()
+}
+
+fn touching(repo_path: &Path, shas: &[String], path: &str) -> AppResult<HashSet<String>> {
+  let shas: Vec<&str> = shas.iter().map(String::as_str).collect();
+  git::touching(repo_path, &shas, |location| under(location, path))
+}
+
+impl std::fmt::Display for LogFilter {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let mut words = vec![];
+    for (prefix, value) in [("author", &self.author), ("path", &self.path)] {
+      match value {
+        Some(value) if value.contains(char::is_whitespace) => words.push(format!("{prefix}:\"{value}\"")),
+        Some(value) => words.push(format!("{prefix}:{value}")),
+        None => {}
+      }
+    }
+    if let Some(message) = &self.message {
+      words.push(message.as_str().to_string());
+    }
+    write!(f, "{}", words.join(" "))
+  }
+}
+
+/// Splits the value of an `author:` or `path:` word from the rest of a query.
+fn prefixed_value(text: &str) -> Result<(String, &str), FilterError> {
+  match text.strip_prefix('"') {
+    Some(quoted) => {
+      let end = quoted
+        .find('"')
+        .ok_or_else(|| FilterError::Query("missing closing quote".to_string()))?;
+      Ok((quoted[..end].to_string(), &quoted[end + 1..]))
+    }
+    None => {
+      let end = text.find(char::is_whitespace).unwrap_or(text.len());
+      Ok((text[..end].to_string(), &text[end..]))
+    }
+  }
+}
+
+/// Whether `location` is the file `path`, or is inside the directory `path`.
+fn under(location: &str, path: &str) -> bool {
+  path.is_empty()
+    || location
+      .strip_prefix(path)
+      .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn log(author: &str, message: &str) -> Log {
+    Log {
+      author: author.into(),
+      full_sha: "abc1234".into(),
+      message: message.into(),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn filter_parse() {
+    let filter = LogFilter::parse(r#"fix author:"Alice Smith" path:src/ .*typo"#).unwrap();
+    assert_eq!(filter.author.as_deref(), Some("Alice Smith"));
+    assert_eq!(filter.path.as_deref(), Some("src"));
+    assert_eq!(filter.message.unwrap().as_str(), "fix .*typo");
+
+    assert!(LogFilter::parse("").unwrap().is_empty());
+    assert!(matches!(LogFilter::parse("fix("), Err(FilterError::Regex(_))));
+    assert!(matches!(LogFilter::parse("author:\"alice"), Err(FilterError::Query(_))));
+  }
+
+  #[test]
+  fn filter_display_round_trips() {
+    let query = r#"author:"Alice Smith" path:src fix\d+ typo"#;
+    assert_eq!(LogFilter::parse(query).unwrap().to_string(), query);
+  }
+
+  #[test]
+  fn filter_matches_author_and_message() {
+    let repo = Repo::default();
+    let filter = LogFilter::parse("author:ali ^Fix").unwrap();
+    assert!(filter.matches(&repo, &log("Alice", "Fix typo"), None));
+    assert!(!filter.matches(&repo, &log("Bob", "Fix typo"), None));
+    assert!(!filter.matches(&repo, &log("Alice", "Add typo"), None));
+
+    let filter = LogFilter::parse("typo$").unwrap();
+    assert!(filter.matches(&repo, &log("Alice", "Fix typo\n"), None));
+  }
+
+  #[test]
+  fn filter_matches_touched_paths() {
+    let repo = Repo::default();
+    let filter = LogFilter::parse("path:src").unwrap();
+    let touched = HashSet::from(["abc1234".to_string()]);
+    assert!(filter.matches(&repo, &log("Alice", "Fix"), Some(&touched)));
+    assert!(!filter.matches(&repo, &log("Alice", "Fix"), Some(&HashSet::new())));
+    assert!(!filter.matches(&repo, &log("Alice", "Fix"), None));
+  }
+
+  #[test]
+  fn filter_skips_excluded_authors() {
+    let repo = Repo {
+      exclude_authors: vec!["dependabot[bot]".into()],
+      ..Default::default()
+    };
+    let filter = LogFilter::default();
+    assert!(!filter.matches(&repo, &log("dependabot[bot]", "Bump gix"), None));
+    assert!(filter.matches(&repo, &log("Alice", "Bump gix"), None));
+  }
+
+  #[test]
+  fn under_path() {
+    assert!(under("src/app/mod.rs", "src"));
+    assert!(under("src", "src"));
+    assert!(!under("srcs/mod.rs", "src"));
+    assert!(under("README.md", ""));
+  }
+}
@@ -0,0 +1,239 @@
+//! Links to the web pages of the forges that host remotes.
+
+use crate::app::AppResult;
+use crate::git;
+use crate::git::repo::Repo;
+use serde::Deserialize;
+
+/// The command that opens a URL in the system's default browser.
+#[cfg(target_os = "macos")]
+pub const OPEN_COMMAND: &str = "open";
+#[cfg(not(target_os = "macos"))]
+pub const OPEN_COMMAND: &str = "xdg-open";
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+  Bitbucket,
+  Forgejo,
+  Gitea,
+  #[serde(rename = "github")]
+  GitHub,
+  #[serde(rename = "gitlab")]
+  GitLab,
+  Sourcehut,
+}
+
+impl ForgeKind {
+  /// Guesses the forge from the host of an origin.
+  fn detect(host: &str) -> Option<ForgeKind> {
+    if host.contains("github") {
+      Some(ForgeKind::GitHub)
+    } else if host.contains("gitlab") {
+      Some(ForgeKind::GitLab)
+    } else if host.contains("bitbucket") {
+      Some(ForgeKind::Bitbucket)
+    } else if host == "codeberg.org" || host.contains("forgejo") {
+      Some(ForgeKind::Forgejo)
+    } else if host.contains("gitea") {
+      Some(ForgeKind::Gitea)
+    } else if host.ends_with("sr.ht") {
+      Some(ForgeKind::Sourcehut)
+    } else {
+      None
+    }
+  }
+}
+
+/// Failures building a link to a forge.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ForgeError {
+  /// The origin has no host and path to link to, ie a local path.
+  NoRepository(String),
+  /// The forge could not be guessed from the origin's host.
+  Undetected(String),
+}
+
+impl std::fmt::Display for ForgeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      ForgeError::NoRepository(origin) => write!(f, "unable to find a forge repository for origin `{origin}`"),
+      ForgeError::Undetected(host) => write!(f, "unable to detect the forge at `{host}`, set `forge` on the remote"),
+    }
+  }
+}
+impl std::error::Error for ForgeError {}
+
+/// A repository on a forge, ie `https://gitlab.com` and `owner/repo`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Forge {
+  pub kind: ForgeKind,
+  /// The scheme and host of the forge's web pages, without a trailing `/`.
+  pub base_url: String,
+  /// The path of the repository on the forge, ie `owner/repo`.
+  pub repository: String,
+}
+
+impl Forge {
+  /// The forge hosting `repo`, from its `forge` and `forge_url` when set, or
+  /// else guessed from its origin, falling back to `default`.
+  pub fn for_repo(repo: &Repo, default: Option<ForgeKind>) -> AppResult<Forge> {
+    let (host, segments) =
+      git::origin_parts(&repo.origin).ok_or_else(|| ForgeError::NoRepository(repo.origin.clone()))?;
+    if host == "local" && repo.forge_url.is_none() {
+      return Err(Box::new(ForgeError::NoRepository(repo.origin.clone())));
+    }
+
+    let kind = repo
+      .forge
+      .or_else(|| ForgeKind::detect(&host))
+      .or(default)
+      .ok_or_else(|| ForgeError::Undetected(host.clone()))?;
+    let base_url = match &repo.forge_url {
+      Some(url) => url.trim_end_matches('/').to_string(),
+      None => format!("https://{host}"),
+    };
+
+    Ok(Forge {
+      kind,
+      base_url,
+      repository: segments.join("/"),
+    })
+  }
+
+  pub fn home_url(&self) -> String {
+    format!("{}/{}", self.base_url, self.repository)
+  }
+
+  /// The changes from `from` to `to`. Sourcehut has no compare view, so its
+  /// log of `to` is used instead.
+  pub fn compare_url(&self, from: &str, to: &str) -> String {
+    let home = self.home_url();
+    match self.kind {
+      ForgeKind::Bitbucket => format!("{home}/branches/compare/{to}%0D{from}#diff"),
+      ForgeKind::Forgejo | ForgeKind::Gitea => format!("{home}/compare/{from}...{to}"),
+      ForgeKind::GitHub => format!("{home}/compare/{from}..{to}?diff=split"),
+      ForgeKind::GitLab => format!("{home}/-/compare/{from}...{to}"),
+      ForgeKind::Sourcehut => format!("{home}/log/{to}"),
+    }
+  }
+
+  pub fn commit_url(&self, sha: &str) -> String {
+    let home = self.home_url();
+    match self.kind {
+      ForgeKind::Bitbucket => format!("{home}/commits/{sha}"),
+      ForgeKind::Forgejo | ForgeKind::Gitea | ForgeKind::GitHub | ForgeKind::Sourcehut => {
+        format!("{home}/commit/{sha}")
+      }
+      ForgeKind::GitLab => format!("{home}/-/commit/{sha}"),
+    }
+  }
+
+  pub fn branch_url(&self, branch: &str) -> String {
+    let home = self.home_url();
+    match self.kind {
+      ForgeKind::Bitbucket => format!("{home}/src/{branch}"),
+      ForgeKind::Forgejo | ForgeKind::Gitea => format!("{home}/src/branch/{branch}"),
+      ForgeKind::GitHub | ForgeKind::Sourcehut => format!("{home}/tree/{branch}"),
+      ForgeKind::GitLab => format!("{home}/-/tree/{branch}"),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn forge(origin: &str, kind: Option<ForgeKind>, url: Option<&str>) -> AppResult<Forge> {
+    let repo = Repo {
+      forge: kind,
+      forge_url: url.map(String::from),
+      origin: origin.into(),
+      ..Default::default()
+    };
+    Forge::for_repo(&repo, None)
+  }
+
+  #[test]
+  fn forge_detected_from_origin() {
+    let cases = [
+      ("git@github.com:synchronal/dyd.git", ForgeKind::GitHub),
+      ("https://gitlab.com/group/sub/project.git", ForgeKind::GitLab),
+      ("git@bitbucket.org:team/repo.git", ForgeKind::Bitbucket),
+      ("https://codeberg.org/owner/repo.git", ForgeKind::Forgejo),
+      ("https://gitea.example.com/owner/repo", ForgeKind::Gitea),
+      ("git@git.sr.ht:~owner/repo", ForgeKind::Sourcehut),
+    ];
+    for (origin, kind) in cases {
+      assert_eq!(forge(origin, None, None).unwrap().kind, kind, "{origin}");
+    }
+  }
+
+  #[test]
+  fn forge_configured_with_custom_host() {
+    let forge = forge(
+      "git@ssh.code.example.com:team/app.git",
+      Some(ForgeKind::GitLab),
+      Some("https://code.example.com/"),
+    )
+    .unwrap();
+
+    assert_eq!(
+      forge.compare_url("abc1234", "main"),
+      "https://code.example.com/team/app/-/compare/abc1234...main"
+    );
+    assert_eq!(
+      forge.commit_url("abc1234"),
+      "https://code.example.com/team/app/-/commit/abc1234"
+    );
+    assert_eq!(
+      forge.branch_url("main"),
+      "https://code.example.com/team/app/-/tree/main"
+    );
+  }
+
+  #[test]
+  fn forge_undetected() {
+    let error = forge("git@code.example.com:team/app.git", None, None).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      ForgeError::Undetected("code.example.com".into()).to_string()
+    );
+  }
+
+  #[test]
+  fn forge_urls() {
+    let cases = [
+      (
+        "git@bitbucket.org:team/repo.git",
+        "https://bitbucket.org/team/repo/branches/compare/main%0Dabc#diff",
+        "https://bitbucket.org/team/repo/commits/abc",
+        "https://bitbucket.org/team/repo/src/main",
+      ),
+      (
+        "https://codeberg.org/owner/repo.git",
+        "https://codeberg.org/owner/repo/compare/abc...main",
+        "https://codeberg.org/owner/repo/commit/abc",
+        "https://codeberg.org/owner/repo/src/branch/main",
+      ),
+      (
+        "git@github.com:synchronal/dyd.git",
+        "https://github.com/synchronal/dyd/compare/abc..main?diff=split",
+        "https://github.com/synchronal/dyd/commit/abc",
+        "https://github.com/synchronal/dyd/tree/main",
+      ),
+      (
+        "git@git.sr.ht:~owner/repo",
+        "https://git.sr.ht/~owner/repo/log/main",
+        "https://git.sr.ht/~owner/repo/commit/abc",
+        "https://git.sr.ht/~owner/repo/tree/main",
+      ),
+    ];
+    for (origin, compare, commit, branch) in cases {
+      let forge = forge(origin, None, None).unwrap();
+      assert_eq!(forge.compare_url("abc", "main"), compare);
+      assert_eq!(forge.commit_url("abc"), commit);
+      assert_eq!(forge.branch_url("main"), branch);
+    }
+  }
+}
@@ -1,13 +1,100 @@
 use crate::app::AppResult;
+use gix::refs::transaction::PreviousValue;
 use gix::remote::Direction;
 use log;
+use serde::Deserialize;
+use std::collections::HashSet;
 use std::error::Error;
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 
+pub mod patch;
 pub mod repo;
 
+/// Failures updating a cached clone that are not errors from gix itself.
+#[derive(Debug, Eq, PartialEq)]
+pub enum GitError {
+  /// HEAD does not point at a branch.
+  DetachedHead,
+  /// The repository has no remote to fetch from.
+  NoRemote,
+  /// The branch has no remote-tracking branch to update from.
+  NoUpstream(String),
+  /// The branch has diverged from its remote-tracking branch.
+  NotFastForward(String),
+}
+
+impl std::fmt::Display for GitError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    match self {
+      GitError::DetachedHead => write!(f, "HEAD is detached"),
+      GitError::NoRemote => write!(f, "unable to find remote"),
+      GitError::NoUpstream(branch) => write!(f, "branch `{branch}` has no upstream"),
+      GitError::NotFastForward(branch) => write!(f, "branch `{branch}` cannot be fast-forwarded to its upstream"),
+    }
+  }
+}
+impl std::error::Error for GitError {}
+
+/// Derives a stable directory for caching a clone of `origin`, in the form
+/// `<host>/<owner>/<repo>`. A trailing `.git` is ignored, so that `foo.git`
+/// and `foo` share a directory. Origins on the local filesystem are cached
+/// under `local/`.
+pub fn cache_path(origin: &str) -> Option<PathBuf> {
+  let (host, segments) = origin_parts(origin)?;
+  let mut path = PathBuf::from(host);
+  path.extend(segments);
+  Some(path)
+}
+
+/// Splits `origin` into its lowercased host and the segments of its path,
+/// without a trailing `.git`. The host of origins on the local filesystem is
+/// `local`. `.` and `..` segments are resolved, and a path with a `..` above
+/// its start is rejected.
+pub fn origin_parts(origin: &str) -> Option<(String, Vec<&str>)> {
+  let origin = origin.trim_end_matches('/');
+  let origin = origin.strip_suffix(".git").unwrap_or(origin);
+
+  let (host, repo_path) = if let Some((_scheme, rest)) = origin.split_once("://") {
+    let (authority, repo_path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = authority
+      .rsplit_once('@')
+      .map_or(authority, |(_user, host)| host);
+    let host = host.split_once(':').map_or(host, |(host, _port)| host);
+    (host, repo_path)
+  } else if let Some((authority, repo_path)) = origin.split_once(':')
+    && !authority.contains('/')
+  {
+    let host = authority
+      .rsplit_once('@')
+      .map_or(authority, |(_user, host)| host);
+    (host, repo_path)
+  } else {
+    ("local", origin)
+  };
+
+  let host = if host.is_empty() {
+    "local".to_string()
+  } else {
+    host.to_lowercase()
+  };
+  let mut segments: Vec<&str> = vec![];
+  for segment in repo_path.split('/') {
+    match segment {
+      "" | "." => {}
+      ".." => {
+        segments.pop()?;
+      }
+      segment => segments.push(segment),
+    }
+  }
+
+  if segments.is_empty() {
+    return None;
+  }
+  Some((host, segments))
+}
+
 pub fn clone_repo(origin: &str, path: &Path) -> Result<(), Box<dyn Error>> {
   log::info!("starting git clone: remote: \"{origin}\", path: {path:?}");
   std::fs::create_dir_all(path)?;
@@ -26,7 +113,31 @@ pub fn clone_repo(origin: &str, path: &Path) -> Result<(), Box<dyn Error>> {
   Ok(())
 }
 
-pub fn logs(path: &Path, branch: Option<&str>) -> AppResult<Vec<repo::Log>> {
+/// Bounds on how much history to walk when reading logs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LogLimits {
+  /// Commits older than this are not read.
+  pub cutoff: chrono::DateTime<chrono::Utc>,
+  /// A safety cap on the number of commits read, regardless of `cutoff`.
+  pub max_commits: usize,
+}
+
+/// Which commits to read from history.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryMode {
+  /// Every commit reachable from the tip.
+  #[default]
+  All,
+  /// Only commits on the first-parent chain of the tip. The commits brought
+  /// in by each merge are read as that merge's [`merged`](repo::Log::merged) logs.
+  FirstParent,
+  /// Only merge commits reachable from the tip.
+  MergesOnly,
+}
+
+/// Reads commits reachable from the tip of `branch`, or HEAD, newest first.
+pub fn logs(path: &Path, branch: Option<&str>, mode: HistoryMode, limits: LogLimits) -> AppResult<repo::History> {
   let repo = gix::discover(path)?;
 
   let tip = match branch {
@@ -38,17 +149,151 @@ pub fn logs(path: &Path, branch: Option<&str>) -> AppResult<Vec<repo::Log>> {
   };
 
   let mut logs = Vec::new();
+  let mut truncated = false;
 
-  for info in repo.rev_walk([tip]).all()?.take(400) {
+  let mut walk = repo.rev_walk([tip]).sorting(by_commit_time(limits));
+  if mode == HistoryMode::FirstParent {
+    walk = walk.first_parent_only();
+  }
+
+  for info in walk.all()? {
+    if logs.len() == limits.max_commits {
+      truncated = true;
+      break;
+    }
     let info = info?;
-    let commit = repo.find_commit(info.id)?;
-    logs.push(repo::Log::try_from(commit)?);
+    let parent_ids: Vec<gix::ObjectId> = info.parent_ids().map(|id| id.detach()).collect();
+    if mode == HistoryMode::MergesOnly && parent_ids.len() < 2 {
+      continue;
+    }
+
+    let mut log = repo::Log::try_from(repo.find_commit(info.id)?)?;
+    if mode == HistoryMode::FirstParent
+      && let Some((first_parent, merged_parents)) = parent_ids.split_first()
+      && !merged_parents.is_empty()
+    {
+      log.merged = merged_logs(&repo, *first_parent, merged_parents, limits)?;
+    }
+    logs.push(log);
   }
 
+  Ok(repo::History { logs, truncated })
+}
+
+/// Commits brought in by a merge: those reachable from its other parents,
+/// but not from its first parent.
+fn merged_logs(
+  repo: &gix::Repository,
+  first_parent: gix::ObjectId,
+  merged_parents: &[gix::ObjectId],
+  limits: LogLimits,
+) -> AppResult<Vec<repo::Log>> {
+  let walk = repo
+    .rev_walk(merged_parents.iter().copied())
+    .with_hidden([first_parent])
+    .sorting(gix::revision::walk::Sorting::ByCommitTime(
+      gix::traverse::commit::simple::CommitTimeOrder::NewestFirst,
+    ))
+    .all()?;
+
+  let mut logs = Vec::new();
+  for info in walk.take(limits.max_commits) {
+    logs.push(repo::Log::try_from(repo.find_commit(info?.id)?)?);
+  }
   Ok(logs)
 }
 
-pub fn pull_repo(path: &Path) -> AppResult<()> {
+fn by_commit_time(limits: LogLimits) -> gix::revision::walk::Sorting {
+  gix::revision::walk::Sorting::ByCommitTimeCutoff {
+    order: gix::traverse::commit::simple::CommitTimeOrder::NewestFirst,
+    seconds: limits.cutoff.timestamp(),
+  }
+}
+
+/// The files changed by the commit `sha`, compared with its first parent.
+pub fn changes(path: &Path, sha: &str) -> AppResult<Vec<repo::FileChange>> {
+  let repo = gix::open(path)?;
+  let (parent_tree, tree) = first_parent_trees(&repo, sha)?;
+
+  let mut cache = repo.diff_resource_cache_for_tree_diff()?;
+  let mut changes = vec![];
+  parent_tree
+    .changes()?
+    .for_each_to_obtain_tree(&tree, |change| {
+      if !change.entry_mode().is_tree() {
+        let counts = change
+          .diff(&mut cache)
+          .ok()
+          .and_then(|mut platform| platform.line_counts().ok())
+          .flatten();
+        changes.push(repo::FileChange {
+          added: counts.map_or(0, |counts| counts.insertions),
+          binary: counts.is_none(),
+          path: change.location().to_string(),
+          removed: counts.map_or(0, |counts| counts.removals),
+        });
+      }
+      cache.clear_resource_cache_keep_allocation();
+      Ok::<_, std::convert::Infallible>(std::ops::ControlFlow::Continue(()))
+    })?;
+  Ok(changes)
+}
+
+/// The full shas of those of `shas` that change a file whose path matches
+/// `predicate`, compared with their first parent.
+pub fn touching(path: &Path, shas: &[&str], predicate: impl Fn(&str) -> bool) -> AppResult<HashSet<String>> {
+  let repo = gix::open(path)?;
+  let mut touching = HashSet::new();
+  for sha in shas {
+    let (parent_tree, tree) = first_parent_trees(&repo, sha)?;
+    let mut found = false;
+    parent_tree
+      .changes()?
+      .for_each_to_obtain_tree(&tree, |change| {
+        found = found || (!change.entry_mode().is_tree() && predicate(&change.location().to_string()));
+        Ok::<_, std::convert::Infallible>(std::ops::ControlFlow::Continue(()))
+      })?;
+    if found {
+      touching.insert(sha.to_string());
+    }
+  }
+  Ok(touching)
+}
+
+/// The full shas of the commits reachable from `sha`, including itself, back
+/// to the cutoff of `limits`. `None` when `sha` is not a commit in the repo.
+pub fn reachable(path: &Path, sha: &str, limits: LogLimits) -> AppResult<Option<HashSet<String>>> {
+  let repo = gix::open(path)?;
+  let Ok(id) = gix::ObjectId::from_hex(sha.as_bytes()) else {
+    return Ok(None);
+  };
+  if repo.find_commit(id).is_err() {
+    return Ok(None);
+  }
+  let mut reachable = HashSet::new();
+  for info in repo.rev_walk([id]).sorting(by_commit_time(limits)).all()? {
+    reachable.insert(info?.id.to_string());
+  }
+  Ok(Some(reachable))
+}
+
+/// The trees of the first parent of `sha`, or the empty tree for a root
+/// commit, and of `sha` itself.
+fn first_parent_trees<'repo>(
+  repo: &'repo gix::Repository,
+  sha: &str,
+) -> AppResult<(gix::Tree<'repo>, gix::Tree<'repo>)> {
+  let commit = repo.rev_parse_single(sha)?.object()?.try_into_commit()?;
+  let tree = commit.tree()?;
+  let parent_tree = match commit.parent_ids().next() {
+    Some(parent) => parent.object()?.try_into_commit()?.tree()?,
+    None => repo.empty_tree(),
+  };
+  Ok((parent_tree, tree))
+}
+
+/// Fetches from the default remote without touching the worktree.
+pub fn fetch_repo(path: &Path) -> AppResult<()> {
   log::info!("starting git fetch: {path:?}");
   let repo = gix::discover(path)?;
   log::debug!("repo: {repo:?}");
@@ -56,9 +301,8 @@ pub fn pull_repo(path: &Path) -> AppResult<()> {
   let remote = match head.into_remote(Direction::Fetch) {
     Some(r) => r?,
     None => {
-      log::error!("failed git fetch: {path:?}, reason: \"unable to find remote\"");
       log::debug!("failed repo: {repo:?}");
-      return Err("Unable to fetch remote".into());
+      return Err(Box::new(GitError::NoRemote));
     }
   };
 
@@ -68,23 +312,333 @@ pub fn pull_repo(path: &Path) -> AppResult<()> {
     .receive(gix::progress::Discard, &AtomicBool::default())?;
 
   log::info!("finished git fetch: {path:?}");
-  log::info!("starting git merge: {path:?}");
 
-  Command::new("git")
-    .args(["merge", "--no-edit", "--ff-only", "--quiet", "--no-commit"])
-    .current_dir(path)
-    .output()
-    .unwrap();
+  Ok(())
+}
 
-  log::info!("finished git merge: {path:?}");
+/// The URL of the default fetch remote of the repository at `path`.
+pub fn origin_url(path: &Path) -> AppResult<String> {
+  let repo = gix::open(path)?;
+  let remote = match repo.find_default_remote(Direction::Fetch) {
+    Some(r) => r?,
+    None => return Err(Box::new(GitError::NoRemote)),
+  };
+  match remote.url(Direction::Fetch) {
+    Some(url) => Ok(url.to_bstring().to_string()),
+    None => Err("Unable to find remote url".into()),
+  }
+}
 
+pub fn pull_repo(path: &Path) -> AppResult<()> {
+  fetch_repo(path)?;
+  fast_forward(path)
+}
+
+/// Fast-forwards the checked out branch to its remote-tracking branch, and
+/// updates the index and worktree to match.
+pub fn fast_forward(path: &Path) -> AppResult<()> {
+  log::info!("starting git fast-forward: {path:?}");
+  let repo = gix::open(path)?;
+  let head = repo.head()?;
+
+  let branch = head
+    .referent_name()
+    .ok_or(GitError::DetachedHead)?
+    .to_owned();
+  let branch_name = branch.shorten().to_string();
+  let tracking = match repo.branch_remote_tracking_ref_name(branch.as_ref(), Direction::Fetch) {
+    Some(name) => name?,
+    None => return Err(Box::new(GitError::NoUpstream(branch_name))),
+  };
+  let tip = repo
+    .find_reference(tracking.as_ref())?
+    .into_fully_peeled_id()?
+    .detach();
+
+  let previous = match head.id() {
+    Some(id) if id == tip => {
+      log::info!("finished git fast-forward: {path:?}, already up to date");
+      return Ok(());
+    }
+    Some(id) => {
+      if repo.merge_base(id, tip)? != id {
+        return Err(Box::new(GitError::NotFastForward(branch_name)));
+      }
+      Some(id.detach())
+    }
+    None => None,
+  };
+
+  let constraint = match previous {
+    Some(id) => PreviousValue::MustExistAndMatch(gix::refs::Target::Object(id)),
+    None => PreviousValue::MustNotExist,
+  };
+  repo.reference(branch, tip, constraint, format!("dyd: fast-forward to {tip}"))?;
+  checkout(&repo, previous, tip)?;
+
+  log::info!("finished git fast-forward: {path:?}");
+  Ok(())
+}
+
+/// Updates the index and worktree from the tree of `previous`, or from an
+/// empty tree when the branch was unborn, to the tree of `commit`. Only the
+/// files that differ between the two trees are written or removed.
+fn checkout(repo: &gix::Repository, previous: Option<gix::ObjectId>, commit: gix::ObjectId) -> AppResult<()> {
+  let Some(workdir) = repo.workdir() else {
+    return Ok(());
+  };
+  let tree = repo.find_commit(commit)?.tree()?;
+  let previous_tree = match previous {
+    Some(id) => repo.find_commit(id)?.tree()?,
+    None => repo.empty_tree(),
+  };
+
+  let mut changed = HashSet::new();
+  let mut deleted = vec![];
+  previous_tree
+    .changes()?
+    .options(|options| {
+      options.track_path().track_rewrites(None);
+    })
+    .for_each_to_obtain_tree(&tree, |change| {
+      if !change.entry_mode().is_tree() {
+        let location = change.location().to_owned();
+        if matches!(change, gix::object::tree::diff::Change::Deletion { .. }) {
+          deleted.push(location);
+        } else {
+          changed.insert(location);
+        }
+      }
+      Ok::<_, std::convert::Infallible>(std::ops::ControlFlow::Continue(()))
+    })?;
+
+  // Entries that are unchanged, and already in the worktree, keep their stat
+  // and are skipped by the checkout.
+  let previous_index = repo.index_or_empty()?;
+  let mut index = repo.index_from_tree(&tree.id)?;
+  let mut skipped = HashSet::new();
+  for (position, (entry, entry_path)) in index.entries_mut_with_paths().enumerate() {
+    if changed.contains(entry_path) {
+      continue;
+    }
+    if let Some(existing) = previous_index.entry_by_path(entry_path)
+      && existing.id == entry.id
+      && existing.mode == entry.mode
+    {
+      entry.stat = existing.stat;
+      entry.flags.insert(gix::index::entry::Flags::SKIP_WORKTREE);
+      skipped.insert(position);
+    }
+  }
+
+  let mut options = repo.checkout_options(gix::worktree::stack::state::attributes::Source::IdMapping)?;
+  options.overwrite_existing = true;
+
+  gix::worktree::state::checkout(
+    &mut index,
+    workdir,
+    repo.objects.clone().into_arc()?,
+    &gix::progress::Discard,
+    &gix::progress::Discard,
+    &AtomicBool::default(),
+    options,
+  )?;
+
+  for (position, entry) in index.entries_mut().iter_mut().enumerate() {
+    if skipped.contains(&position) {
+      entry.flags.remove(gix::index::entry::Flags::SKIP_WORKTREE);
+    }
+  }
+  for location in deleted {
+    if index.entry_by_path(location.as_ref()).is_none() {
+      let file = workdir.join(gix::path::from_bstr(location.as_ref() as &gix::bstr::BStr));
+      if let Err(err) = std::fs::remove_file(&file) {
+        log::debug!("unable to remove {file:?}: {err}");
+      }
+    }
+  }
+
+  index.write(Default::default())?;
   Ok(())
 }
 
-pub fn switch_branch(path: &Path, branch: &str) {
-  Command::new("git")
-    .args(["switch", branch])
-    .current_dir(path)
-    .output()
-    .unwrap();
+#[cfg(test)]
+mod tests {
+  use super::{HistoryMode, LogLimits, cache_path, clone_repo, logs, pull_repo, reachable};
+  use crate::difftool::DiffRange;
+  use std::path::{Path, PathBuf};
+  use std::time::{Duration, SystemTime};
+
+  fn git(dir: &Path, args: &[&str]) -> String {
+    git_at(dir, None, args)
+  }
+
+  /// Runs git in `dir`, committing at `seconds` since the epoch when given.
+  fn git_at(dir: &Path, seconds: Option<i64>, args: &[&str]) -> String {
+    let mut command = std::process::Command::new("git");
+    if let Some(seconds) = seconds {
+      let date = format!("@{seconds} +0000");
+      command
+        .env("GIT_AUTHOR_DATE", &date)
+        .env("GIT_COMMITTER_DATE", &date);
+    }
+    let output = command
+      .args([
+        "-c",
+        "user.name=dyd",
+        "-c",
+        "user.email=dyd@example.com",
+        "-c",
+        "commit.gpgsign=false",
+      ])
+      .args(args)
+      .current_dir(dir)
+      .output()
+      .unwrap();
+    assert!(output.status.success(), "git {args:?}");
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+  }
+
+  #[test]
+  fn cache_path_ssh() {
+    assert_eq!(
+      cache_path("git@github.com:synchronal/dyd"),
+      Some(PathBuf::from("github.com/synchronal/dyd"))
+    );
+    assert_eq!(
+      cache_path("git@github.com:synchronal/dyd.git"),
+      Some(PathBuf::from("github.com/synchronal/dyd"))
+    );
+    assert_eq!(
+      cache_path("ssh://git@GitHub.com:22/synchronal/dyd.git"),
+      Some(PathBuf::from("github.com/synchronal/dyd"))
+    );
+  }
+
+  #[test]
+  fn cache_path_https() {
+    assert_eq!(
+      cache_path("https://github.com/synchronal/dyd.git"),
+      Some(PathBuf::from("github.com/synchronal/dyd"))
+    );
+    assert_eq!(
+      cache_path("https://user@gitlab.example.com/group/subgroup/dyd/"),
+      Some(PathBuf::from("gitlab.example.com/group/subgroup/dyd"))
+    );
+  }
+
+  #[test]
+  fn cache_path_distinguishes_owners() {
+    assert_ne!(cache_path("git@github.com:a/api"), cache_path("git@github.com:b/api"));
+  }
+
+  #[test]
+  fn cache_path_local() {
+    assert_eq!(cache_path("/src/../dyd.git"), Some(PathBuf::from("local/dyd")));
+    assert_eq!(cache_path("/src/./a/../dyd"), Some(PathBuf::from("local/src/dyd")));
+    assert_eq!(cache_path("../dyd"), None);
+    assert_eq!(cache_path("file:///src/dyd"), Some(PathBuf::from("local/src/dyd")));
+    assert_eq!(cache_path("https://github.com/"), None);
+  }
+
+  #[test]
+  fn pull_repo_only_writes_changed_files() {
+    let root = std::env::temp_dir().join(format!("dyd-checkout-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let origin = root.join("origin");
+    let clone = root.join("clone");
+    std::fs::create_dir_all(&origin).unwrap();
+
+    git(&origin, &["init", "--quiet", "--initial-branch=main"]);
+    std::fs::write(origin.join("a.txt"), "a").unwrap();
+    std::fs::write(origin.join("b.txt"), "b").unwrap();
+    std::fs::write(origin.join("c.txt"), "c").unwrap();
+    git(&origin, &["add", "."]);
+    git(&origin, &["commit", "--quiet", "-m", "first"]);
+
+    clone_repo(origin.to_str().unwrap(), &clone).unwrap();
+    git(&clone, &["config", "user.name", "dyd"]);
+    git(&clone, &["config", "user.email", "dyd@example.com"]);
+    let modified = SystemTime::now() - Duration::from_secs(86_400);
+    std::fs::File::options()
+      .write(true)
+      .open(clone.join("a.txt"))
+      .unwrap()
+      .set_modified(modified)
+      .unwrap();
+
+    std::fs::write(origin.join("b.txt"), "bb").unwrap();
+    git(&origin, &["rm", "--quiet", "c.txt"]);
+    git(&origin, &["commit", "--quiet", "-am", "second"]);
+
+    pull_repo(&clone).unwrap();
+
+    let a = std::fs::metadata(clone.join("a.txt")).unwrap();
+    assert_eq!(a.modified().unwrap(), modified);
+    assert_eq!(std::fs::read_to_string(clone.join("b.txt")).unwrap(), "bb");
+    assert!(!clone.join("c.txt").exists());
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn reachable_leaves_out_commits_merged_later() {
+    let root = std::env::temp_dir().join(format!("dyd-reachable-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    let commit = |seconds, message| {
+      git_at(
+        &root,
+        Some(seconds),
+        &["commit", "--quiet", "--allow-empty", "-m", message],
+      );
+      git(&root, &["rev-parse", "HEAD"])
+    };
+
+    git(&root, &["init", "--quiet", "--initial-branch=main"]);
+    let a = commit(100, "a");
+    git(&root, &["checkout", "--quiet", "-b", "side"]);
+    let c = commit(200, "c");
+    git(&root, &["checkout", "--quiet", "main"]);
+    let b = commit(300, "b");
+    git_at(&root, Some(400), &["merge", "--quiet", "--no-ff", "-m", "m", "side"]);
+
+    let limits = LogLimits {
+      cutoff: chrono::DateTime::UNIX_EPOCH,
+      max_commits: 100,
+    };
+    let read = reachable(&root, &b, limits).unwrap().unwrap();
+    assert!(read.contains(&a) && read.contains(&b));
+    assert!(!read.contains(&c));
+    assert_eq!(reachable(&root, &"0".repeat(40), limits).unwrap(), None);
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn diff_ranges_use_full_shas() {
+    let root = std::env::temp_dir().join(format!("dyd-ranges-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    git(&root, &["init", "--quiet", "--initial-branch=main"]);
+    git_at(&root, Some(100), &["commit", "--quiet", "--allow-empty", "-m", "a"]);
+    let a = git(&root, &["rev-parse", "HEAD"]);
+    git_at(&root, Some(200), &["commit", "--quiet", "--allow-empty", "-m", "b"]);
+    let b = git(&root, &["rev-parse", "HEAD"]);
+
+    let limits = LogLimits {
+      cutoff: chrono::DateTime::UNIX_EPOCH,
+      max_commits: 100,
+    };
+    let history = logs(&root, None, HistoryMode::All, limits).unwrap();
+    let range = DiffRange::commit(&history.logs[0]);
+    assert_eq!(range.from.len(), 40);
+    assert_eq!(range.from, a);
+    assert_eq!(range.to, Some(b.clone()));
+    let range = DiffRange::between(&history.logs[1], &history.logs[0]);
+    assert_eq!(range.from, crate::difftool::EMPTY_TREE);
+    assert_eq!(range.to, Some(b));
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
 }
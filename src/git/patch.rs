@@ -0,0 +1,188 @@
+use crate::app::AppResult;
+
+use gix::diff::blob::platform::prepare_diff::Operation;
+use gix::diff::blob::unified_diff::{ConsumeHunk, ContextSize, DiffLineKind, HunkHeader};
+use gix::diff::blob::{Diff, UnifiedDiff};
+use gix::object::tree::diff::Change;
+use std::path::Path;
+
+/// A line of a patch, without its unified diff prefix.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PatchLine {
+  /// The start of a changed file.
+  File(String),
+  /// The start of a hunk, ie `@@ -1,3 +1,4 @@`.
+  Hunk(String),
+  Context(String),
+  Added(String),
+  Removed(String),
+  /// A file whose changes can not be shown as lines.
+  Binary,
+}
+
+/// The changes between two commits, flattened into lines so that they may be
+/// scrolled through.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Patch {
+  pub lines: Vec<PatchLine>,
+}
+
+impl Patch {
+  /// The index of each line starting a file.
+  pub fn files(&self) -> Vec<usize> {
+    self.starts(|line| matches!(line, PatchLine::File(_)))
+  }
+
+  /// The index of each line starting a hunk.
+  pub fn hunks(&self) -> Vec<usize> {
+    self.starts(|line| matches!(line, PatchLine::Hunk(_)))
+  }
+
+  fn starts(&self, predicate: impl Fn(&PatchLine) -> bool) -> Vec<usize> {
+    self
+      .lines
+      .iter()
+      .enumerate()
+      .filter(|(_index, line)| predicate(line))
+      .map(|(index, _line)| index)
+      .collect()
+  }
+}
+
+/// The first of `starts` after `line`.
+pub fn next_start(starts: &[usize], line: usize) -> Option<usize> {
+  starts.iter().copied().find(|start| *start > line)
+}
+
+/// The last of `starts` before `line`.
+pub fn previous_start(starts: &[usize], line: usize) -> Option<usize> {
+  starts.iter().copied().rfind(|start| *start < line)
+}
+
+/// Reads the changes from `ref_from` to `ref_to` in the repo at `path`.
+pub fn patch(path: &Path, ref_from: &str, ref_to: &str) -> AppResult<Patch> {
+  let repo = gix::open(path)?;
+  let from = repo.rev_parse_single(ref_from)?.object()?.peel_to_tree()?;
+  let to = repo.rev_parse_single(ref_to)?.object()?.peel_to_tree()?;
+
+  let mut cache = repo.diff_resource_cache_for_tree_diff()?;
+  cache.options.skip_internal_diff_if_external_is_configured = false;
+
+  let mut patch = Patch::default();
+  let mut error = None;
+  let outcome = from.changes()?.for_each_to_obtain_tree(&to, |change| {
+    if !change.entry_mode().is_tree() {
+      patch.lines.push(PatchLine::File(file_header(&change)));
+      if let Err(err) = push_hunks(&change, &mut cache, &mut patch.lines) {
+        error = Some(err);
+        return Ok::<_, std::convert::Infallible>(std::ops::ControlFlow::Break(()));
+      }
+    }
+    cache.clear_resource_cache_keep_allocation();
+    Ok(std::ops::ControlFlow::Continue(()))
+  });
+
+  // Breaking out of the diff is reported as a cancellation, hiding the error.
+  match (error, outcome) {
+    (Some(err), _) => Err(err),
+    (None, Err(err)) => Err(Box::new(err)),
+    (None, Ok(_)) => Ok(patch),
+  }
+}
+
+fn file_header(change: &Change<'_, '_, '_>) -> String {
+  match change {
+    Change::Addition { location, .. } => format!("{location} (added)"),
+    Change::Deletion { location, .. } => format!("{location} (deleted)"),
+    Change::Modification { location, .. } => location.to_string(),
+    Change::Rewrite {
+      source_location,
+      location,
+      copy,
+      ..
+    } => {
+      let verb = if *copy { "copied" } else { "renamed" };
+      format!("{location} ({verb} from {source_location})")
+    }
+  }
+}
+
+fn push_hunks(
+  change: &Change<'_, '_, '_>,
+  cache: &mut gix::diff::blob::Platform,
+  lines: &mut Vec<PatchLine>,
+) -> AppResult<()> {
+  let platform = change.diff(cache)?;
+  let prepared = platform.resource_cache.prepare_diff()?;
+  match prepared.operation {
+    Operation::InternalDiff { algorithm } => {
+      let input = prepared.interned_input();
+      let diff = Diff::compute(algorithm, &input);
+      let hunks = UnifiedDiff::new(&diff, &input, Hunks(lines), ContextSize::symmetrical(3));
+      hunks.consume()?;
+    }
+    Operation::ExternalCommand { .. } | Operation::SourceOrDestinationIsBinary => lines.push(PatchLine::Binary),
+  }
+  Ok(())
+}
+
+struct Hunks<'a>(&'a mut Vec<PatchLine>);
+
+impl ConsumeHunk for Hunks<'_> {
+  type Out = ();
+
+  fn consume_hunk(&mut self, header: HunkHeader, lines: &[(DiffLineKind, &[u8])]) -> std::io::Result<()> {
+    self.0.push(PatchLine::Hunk(format!(
+      "@@ -{},{} +{},{} @@",
+      header.before_hunk_start, header.before_hunk_len, header.after_hunk_start, header.after_hunk_len
+    )));
+    self.0.extend(lines.iter().map(|(kind, line)| {
+      let text = String::from_utf8_lossy(line)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+      match kind {
+        DiffLineKind::Context => PatchLine::Context(text),
+        DiffLineKind::Add => PatchLine::Added(text),
+        DiffLineKind::Remove => PatchLine::Removed(text),
+      }
+    }));
+    Ok(())
+  }
+
+  fn finish(self) -> Self::Out {}
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn patch() -> Patch {
+    Patch {
+      lines: vec![
+        PatchLine::File("a.txt".into()),
+        PatchLine::Hunk("@@ -1,1 +1,1 @@".into()),
+        PatchLine::Removed("one".into()),
+        PatchLine::Added("two".into()),
+        PatchLine::Hunk("@@ -9,1 +9,1 @@".into()),
+        PatchLine::Context("nine".into()),
+        PatchLine::File("b.png (added)".into()),
+        PatchLine::Binary,
+      ],
+    }
+  }
+
+  #[test]
+  fn patch_files_and_hunks() {
+    assert_eq!(patch().files(), vec![0, 6]);
+    assert_eq!(patch().hunks(), vec![1, 4]);
+  }
+
+  #[test]
+  fn patch_next_and_previous_start() {
+    let files = patch().files();
+    assert_eq!(next_start(&files, 0), Some(6));
+    assert_eq!(next_start(&files, 6), None);
+    assert_eq!(previous_start(&files, 5), Some(0));
+    assert_eq!(previous_start(&files, 0), None);
+  }
+}
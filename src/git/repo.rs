@@ -1,8 +1,12 @@
 use crate::app::{AppResult, Event};
+use crate::difftool::Difftool;
+use crate::forge::ForgeKind;
 use crate::git;
 use crate::manifest::Remote;
 use crate::semaphore::Semaphore;
 
+use ratatui::style::Color;
+use serde::Deserialize;
 use std::cmp::Ordering;
 use std::error::Error;
 use std::path::{Path, PathBuf};
@@ -14,18 +18,49 @@ pub enum RepoStatus {
   #[default]
   Checking,
   Cloning,
-  Failed,
+  /// The error, followed by each of its sources.
+  Failed(Vec<String>),
+  Fetching,
   Finished,
   Log,
   Pulling,
 }
 
+impl RepoStatus {
+  pub fn failed(err: &dyn Error) -> Self {
+    let mut chain = vec![err.to_string()];
+    let mut source = err.source();
+    while let Some(err) = source {
+      let reason = err.to_string();
+      if chain.last() != Some(&reason) {
+        chain.push(reason);
+      }
+      source = err.source();
+    }
+    RepoStatus::Failed(chain)
+  }
+
+  /// The status in words, for progress lines printed without the UI.
+  pub fn label(&self) -> &'static str {
+    match self {
+      RepoStatus::Checking => "checking",
+      RepoStatus::Cloning => "cloning",
+      RepoStatus::Failed(_) => "failed",
+      RepoStatus::Fetching => "fetching",
+      RepoStatus::Finished => "finished",
+      RepoStatus::Log => "reading logs",
+      RepoStatus::Pulling => "pulling",
+    }
+  }
+}
+
 impl std::fmt::Display for RepoStatus {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       RepoStatus::Checking => write!(f, " ⁇"),
       RepoStatus::Cloning => write!(f, " ⚭"),
-      RepoStatus::Failed => write!(f, " 𝗫"),
+      RepoStatus::Failed(_) => write!(f, " 𝗫"),
+      RepoStatus::Fetching => write!(f, " ⇣"),
       RepoStatus::Finished => write!(f, " ✓"),
       RepoStatus::Log => write!(f, " ☈"),
       RepoStatus::Pulling => write!(f, " ⤵"),
@@ -36,10 +71,28 @@ impl std::fmt::Display for RepoStatus {
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct Repo {
   pub(crate) branch: Option<String>,
+  pub(crate) color: Option<Color>,
+  pub(crate) difftool: Option<Difftool>,
+  /// Whether the difftool template is run with `$SHELL -c`.
+  pub(crate) difftool_shell: bool,
+  /// Whether the difftool draws in the terminal, rather than in its own window.
+  pub(crate) difftool_terminal: bool,
+  /// Authors whose logs are never shown, ie bots.
+  pub(crate) exclude_authors: Vec<String>,
+  pub(crate) fetch: bool,
+  pub(crate) forge: Option<ForgeKind>,
+  pub(crate) forge_url: Option<String>,
+  /// The group the repo is listed under in the Repos pane.
+  pub(crate) group: Option<String>,
+  pub(crate) history: git::HistoryMode,
+  pub(crate) local_path: Option<PathBuf>,
   pub(crate) logs: Vec<Log>,
   pub(crate) name: String,
   pub(crate) origin: String,
+  pub(crate) since: Option<chrono::DateTime<chrono::Utc>>,
   pub(crate) status: RepoStatus,
+  /// Whether `logs` stopped at the `max_commits` cap before reaching the cutoff.
+  pub(crate) truncated: bool,
 }
 
 impl std::fmt::Display for Repo {
@@ -57,8 +110,20 @@ impl From<Remote> for Repo {
   fn from(remote: Remote) -> Self {
     Repo {
       branch: remote.branch,
+      color: remote.color,
+      difftool: remote.difftool,
+      difftool_shell: remote.difftool_shell.unwrap_or_default(),
+      difftool_terminal: remote.difftool_terminal.unwrap_or_default(),
+      exclude_authors: remote.exclude_authors,
+      fetch: remote.fetch,
+      forge: remote.forge,
+      forge_url: remote.forge_url,
+      group: remote.group,
+      history: remote.history.unwrap_or_default(),
+      local_path: remote.path,
       name: remote.name,
       origin: remote.origin,
+      since: remote.since_datetime,
       ..Default::default()
     }
   }
@@ -90,14 +155,109 @@ impl PartialOrd for Repo {
   }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// The order of repos in the Repos pane and in `dyd log`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoSort {
+  /// Repos with the newest commits first.
+  #[default]
+  Recent,
+  Name,
+  /// The order in which remotes appear in the manifest.
+  Manifest,
+  /// By group, then with the newest commits first. Groups are listed under
+  /// headers in the Repos pane.
+  Group,
+}
+
+impl RepoSort {
+  /// The next mode, cycling back to the first.
+  pub fn next(self) -> RepoSort {
+    match self {
+      RepoSort::Recent => RepoSort::Name,
+      RepoSort::Name => RepoSort::Manifest,
+      RepoSort::Manifest => RepoSort::Group,
+      RepoSort::Group => RepoSort::Recent,
+    }
+  }
+
+  /// Compares two repos. Repos are equal in `Manifest` mode, so that a stable
+  /// sort keeps them in manifest order.
+  pub fn compare(&self, a: &Repo, b: &Repo) -> Ordering {
+    match self {
+      RepoSort::Recent => a.cmp(b),
+      RepoSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+      RepoSort::Manifest => Ordering::Equal,
+      RepoSort::Group => (a.group.is_none(), &a.group)
+        .cmp(&(b.group.is_none(), &b.group))
+        .then_with(|| a.cmp(b)),
+    }
+  }
+}
+
+impl std::fmt::Display for RepoSort {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RepoSort::Recent => write!(f, "recent"),
+      RepoSort::Name => write!(f, "name"),
+      RepoSort::Manifest => write!(f, "manifest"),
+      RepoSort::Group => write!(f, "group"),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Log {
   pub author: String,
+  /// The message after the title, including any trailers.
+  pub body: String,
+  /// Names and emails from `Co-authored-by` trailers.
+  pub co_authors: Vec<String>,
   pub commit_datetime: chrono::DateTime<chrono::Utc>,
+  pub committer: String,
+  pub full_sha: String,
+  /// For merges read in first-parent mode, the commits brought in by the merge.
+  pub merged: Vec<Log>,
   pub message: String,
+  /// The full shas of the parents.
+  pub parents: Vec<String>,
   pub sha: String,
 }
 
+impl Log {
+  pub fn is_merge(&self) -> bool {
+    self.parents.len() > 1
+  }
+
+  /// The ref to diff from. For merges this is the first parent, so that the
+  /// diff includes the changes brought in by the merge.
+  pub fn ref_from(&self) -> &str {
+    if self.is_merge() {
+      &self.parents[0]
+    } else {
+      &self.full_sha
+    }
+  }
+}
+
+/// A file changed by a commit.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FileChange {
+  pub added: u32,
+  /// Whether either side is binary, in which case no lines are counted.
+  pub binary: bool,
+  pub path: String,
+  pub removed: u32,
+}
+
+/// The logs read from a repo, newest first.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct History {
+  pub logs: Vec<Log>,
+  /// Whether reading stopped at the `max_commits` cap before reaching the cutoff.
+  pub truncated: bool,
+}
+
 impl Ord for Log {
   fn cmp(&self, other: &Self) -> Ordering {
     if self.commit_datetime > other.commit_datetime {
@@ -128,34 +288,133 @@ impl TryFrom<gix::Commit<'_>> for Log {
     let seconds = commit.time()?.seconds;
     let commit_datetime =
       chrono::DateTime::from_timestamp(seconds, 0).unwrap_or_else(|| chrono::DateTime::from_timestamp(0, 0).unwrap());
-    let message = commit.message()?.title.to_string();
+    let committer = commit.committer()?.name.to_string();
+    let full_sha = commit.id.to_string();
+    let commit_message = commit.message()?;
+    let message = commit_message.title.to_string();
+    let body = commit_message
+      .body
+      .map(|body| body.to_string().trim().to_string())
+      .unwrap_or_default();
+    let co_authors = co_authors(&commit_message);
+    let parents = commit.parent_ids().map(|id| id.to_string()).collect();
 
     Ok(Log {
       author,
+      body,
+      co_authors,
       commit_datetime,
+      committer,
+      full_sha,
       message,
+      parents,
       sha,
+      ..Default::default()
     })
   }
 }
 
+fn co_authors(message: &gix::objs::commit::MessageRef<'_>) -> Vec<String> {
+  message
+    .body()
+    .map(|body| {
+      body
+        .trailers()
+        .filter(|trailer| trailer.token.eq_ignore_ascii_case(b"co-authored-by"))
+        .map(|trailer| trailer.value.to_string())
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
 impl Repo {
+  /// Whether `log` was written by one of `exclude_authors`.
+  pub fn excludes(&self, log: &Log) -> bool {
+    self.exclude_authors.contains(&log.author)
+  }
+
   pub fn update(
+    &self,
+    id: &str,
+    root_path: &Path,
+    limits: git::LogLimits,
+    sender: mpsc::Sender<Event>,
+    semaphore: Arc<Semaphore>,
+  ) -> AppResult<()> {
+    let branch = self.branch.clone();
+    let history = self.history;
+    self.sync(id, root_path, sender, semaphore, move |id, path, sender| {
+      sender
+        .send(Event::RepoStatusChange(id.clone(), RepoStatus::Log))
+        .unwrap();
+
+      match Repo::logs(path, branch.as_deref(), history, limits) {
+        Ok(history) => sender
+          .send(Event::RepoStatusComplete(id.clone(), history))
+          .unwrap(),
+        Err(err) => {
+          log::error!("failed git log: {path:?}, reason: {err}");
+          sender
+            .send(Event::RepoStatusChange(id.clone(), RepoStatus::failed(&*err)))
+            .unwrap();
+        }
+      };
+    })
+  }
+
+  /// Clones or pulls the repo without reading its logs.
+  pub fn fetch(
     &self,
     id: &str,
     root_path: &Path,
     sender: mpsc::Sender<Event>,
     semaphore: Arc<Semaphore>,
   ) -> AppResult<()> {
+    self.sync(id, root_path, sender, semaphore, |id, _path, sender| {
+      sender
+        .send(Event::RepoStatusChange(id, RepoStatus::Finished))
+        .unwrap();
+    })
+  }
+
+  /// Clones or pulls the repo on a background thread, then hands the
+  /// checkout to `then` unless that failed.
+  fn sync(
+    &self,
+    id: &str,
+    root_path: &Path,
+    sender: mpsc::Sender<Event>,
+    semaphore: Arc<Semaphore>,
+    then: impl FnOnce(String, &Path, &mpsc::Sender<Event>) + Send + 'static,
+  ) -> AppResult<()> {
+    if let Err(err) = self.migrate_legacy_path(root_path) {
+      log::error!("failed migrating clone: {}, reason: {err}", self.name);
+    }
     let path = self.path(root_path)?;
     let origin = self.origin.clone();
-    let branch = self.branch.clone();
+    let local = self.local_path.is_some();
+    let fetch = self.fetch;
     let id = id.to_string();
 
     std::thread::spawn(move || {
       let _permit = semaphore.acquire();
 
-      if path.is_dir() {
+      if local {
+        if fetch {
+          sender
+            .send(Event::RepoStatusChange(id.clone(), RepoStatus::Fetching))
+            .unwrap();
+
+          if let Err(err) = git::fetch_repo(&path) {
+            log::error!("failed git fetch: {path:?}, reason: {err}");
+            sender
+              .send(Event::RepoStatusChange(id.clone(), RepoStatus::failed(&*err)))
+              .unwrap();
+
+            return;
+          }
+        }
+      } else if path.is_dir() {
         sender
           .send(Event::RepoStatusChange(id.clone(), RepoStatus::Pulling))
           .unwrap();
@@ -163,7 +422,7 @@ impl Repo {
         if let Err(err) = git::pull_repo(&path) {
           log::error!("failed git pull: {path:?}, reason: {err}");
           sender
-            .send(Event::RepoStatusChange(id.clone(), RepoStatus::Failed))
+            .send(Event::RepoStatusChange(id.clone(), RepoStatus::failed(&*err)))
             .unwrap();
 
           return;
@@ -176,35 +435,101 @@ impl Repo {
         if let Err(err) = git::clone_repo(&origin, &path) {
           log::error!("failed git clone: {path:?}, reason: {err}");
           sender
-            .send(Event::RepoStatusChange(id.clone(), RepoStatus::Failed))
+            .send(Event::RepoStatusChange(id.clone(), RepoStatus::failed(&*err)))
             .unwrap();
 
           return;
         }
       }
-      sender
-        .send(Event::RepoStatusChange(id.clone(), RepoStatus::Log))
-        .unwrap();
-
-      if let Ok(logs) = Repo::logs(&path, branch.as_deref()) {
-        sender
-          .send(Event::RepoStatusComplete(id.clone(), logs))
-          .unwrap();
-      };
+      then(id, &path, &sender);
     });
     Ok(())
   }
 
+  /// The cutoff for this repo's logs, when the manifest overrides `since`
+  /// for its remote, or the given default.
+  pub fn since_or(&self, default: &chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::Utc> {
+    self.since.unwrap_or(*default)
+  }
+
+  /// Whether `log` is newer than this repo's `since` and, when there is an
+  /// `until`, older than that.
+  pub fn in_window(
+    &self,
+    log: &Log,
+    since: &chrono::DateTime<chrono::Utc>,
+    until: Option<&chrono::DateTime<chrono::Utc>>,
+  ) -> bool {
+    log.commit_datetime > self.since_or(since) && until.is_none_or(|until| log.commit_datetime < *until)
+  }
+
+  /// The newest log older than `until`.
+  pub fn newest_until(&self, until: &chrono::DateTime<chrono::Utc>) -> Option<&Log> {
+    self
+      .logs
+      .iter()
+      .filter(|log| log.commit_datetime < *until)
+      .min()
+  }
+
   pub fn path(&self, root: &Path) -> AppResult<PathBuf> {
-    if let Some(path) = Path::new(&self.origin).file_name() {
+    if let Some(path) = &self.local_path {
+      Ok(path.clone())
+    } else if let Some(path) = git::cache_path(&self.origin) {
       Ok(root.join(path))
     } else {
       Err(format!("Unable to determine local path for {}", self.name).into())
     }
   }
 
-  fn logs(path: &Path, branch: Option<&str>) -> AppResult<Vec<Log>> {
-    git::logs(path, branch)
+  /// Moves a clone from the flat layout used by earlier versions of dyd,
+  /// ie `<root>/<basename>`, into the directory given by [`Repo::path`]. The
+  /// clone is only moved when its origin matches this repo's origin.
+  pub fn migrate_legacy_path(&self, root: &Path) -> AppResult<()> {
+    if self.local_path.is_some() {
+      return Ok(());
+    }
+    let path = self.path(root)?;
+    let Some(basename) = Path::new(&self.origin).file_name() else {
+      return Ok(());
+    };
+    let legacy_path = root.join(basename);
+
+    if path.exists() || !legacy_path.join(".git").is_dir() {
+      return Ok(());
+    }
+    if git::origin_url(&legacy_path)
+      .ok()
+      .and_then(|url| git::cache_path(&url))
+      != git::cache_path(&self.origin)
+    {
+      return Ok(());
+    }
+
+    log::info!("migrating clone: {legacy_path:?} -> {path:?}");
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(&legacy_path, &path)?;
+    Ok(())
+  }
+
+  fn logs(path: &Path, branch: Option<&str>, history: git::HistoryMode, limits: git::LogLimits) -> AppResult<History> {
+    git::logs(path, branch, history, limits)
+  }
+
+  /// Limits for reading this repo's logs: a `margin` before its `since`, so
+  /// that earlier dates may be selected without reading logs again.
+  pub fn log_limits(
+    &self,
+    since: &chrono::DateTime<chrono::Utc>,
+    margin: chrono::Duration,
+    max_commits: usize,
+  ) -> git::LogLimits {
+    git::LogLimits {
+      cutoff: self.since_or(since) - margin,
+      max_commits,
+    }
   }
 }
 
@@ -212,6 +537,76 @@ impl Repo {
 mod tests {
   use super::*;
 
+  #[derive(Debug)]
+  struct PullError(git::GitError);
+
+  impl std::fmt::Display for PullError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+      write!(f, "unable to pull")
+    }
+  }
+  impl Error for PullError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+      Some(&self.0)
+    }
+  }
+
+  #[test]
+  fn test_repo_status_failed_includes_sources() {
+    let err = PullError(git::GitError::NoUpstream("main".into()));
+    assert_eq!(
+      RepoStatus::failed(&err),
+      RepoStatus::Failed(vec!["unable to pull".into(), "branch `main` has no upstream".into()])
+    );
+  }
+
+  #[test]
+  fn test_log_limits_subtract_margin_from_since() {
+    let since = chrono::DateTime::from_timestamp(10 * 86400, 0).unwrap();
+    let margin = chrono::Duration::days(2);
+
+    let repo = Repo::default();
+    assert_eq!(
+      repo.log_limits(&since, margin, 100),
+      git::LogLimits {
+        cutoff: chrono::DateTime::from_timestamp(8 * 86400, 0).unwrap(),
+        max_commits: 100,
+      }
+    );
+
+    let repo = Repo {
+      since: Some(chrono::DateTime::from_timestamp(5 * 86400, 0).unwrap()),
+      ..Default::default()
+    };
+    assert_eq!(
+      repo.log_limits(&since, margin, 100).cutoff,
+      chrono::DateTime::from_timestamp(3 * 86400, 0).unwrap()
+    );
+  }
+
+  #[test]
+  fn test_in_window_between_since_and_until() {
+    let at = |seconds| chrono::DateTime::from_timestamp(seconds, 0).unwrap();
+    let log = |seconds, sha: &str| Log {
+      commit_datetime: at(seconds),
+      sha: sha.into(),
+      ..Default::default()
+    };
+    let repo = Repo {
+      logs: vec![log(3000, "c"), log(2000, "b"), log(1000, "a")],
+      ..Default::default()
+    };
+
+    assert!(repo.in_window(&repo.logs[0], &at(500), None));
+    assert!(!repo.in_window(&repo.logs[0], &at(500), Some(&at(2500))));
+    assert!(repo.in_window(&repo.logs[1], &at(500), Some(&at(2001))));
+    assert!(!repo.in_window(&repo.logs[1], &at(500), Some(&at(2000))));
+    assert!(!repo.in_window(&repo.logs[2], &at(1000), Some(&at(2500))));
+
+    assert_eq!(repo.newest_until(&at(2500)).map(|log| log.sha.as_str()), Some("b"));
+    assert_eq!(repo.newest_until(&at(500)), None);
+  }
+
   #[test]
   fn test_log_ordering_newer_first() {
     let newer = Log {
@@ -219,12 +614,14 @@ mod tests {
       commit_datetime: chrono::DateTime::from_timestamp(1000, 0).unwrap(),
       message: "Second".into(),
       sha: "abc".into(),
+      ..Default::default()
     };
     let older = Log {
       author: "Bob".into(),
       commit_datetime: chrono::DateTime::from_timestamp(500, 0).unwrap(),
       message: "First".into(),
       sha: "def".into(),
+      ..Default::default()
     };
 
     assert_eq!(newer.cmp(&older), Ordering::Less);
@@ -238,15 +635,48 @@ mod tests {
       commit_datetime: chrono::DateTime::from_timestamp(1000, 0).unwrap(),
       message: "BBB".into(),
       sha: "abc".into(),
+      ..Default::default()
     };
     let log_b = Log {
       author: "Bob".into(),
       commit_datetime: chrono::DateTime::from_timestamp(1000, 0).unwrap(),
       message: "AAA".into(),
       sha: "def".into(),
+      ..Default::default()
     };
 
     assert_eq!(log_a.cmp(&log_b), Ordering::Less);
     assert_eq!(log_b.cmp(&log_a), Ordering::Greater);
   }
+
+  #[test]
+  fn test_log_ref_from_merge_is_first_parent() {
+    let commit = Log {
+      full_sha: "abc0000".into(),
+      sha: "abc".into(),
+      parents: vec!["def".into()],
+      ..Default::default()
+    };
+    let merge = Log {
+      full_sha: "abc0000".into(),
+      sha: "abc".into(),
+      parents: vec!["def".into(), "ghi".into()],
+      ..Default::default()
+    };
+
+    assert_eq!(commit.ref_from(), "abc0000");
+    assert_eq!(merge.ref_from(), "def");
+  }
+
+  #[test]
+  fn test_co_authors_from_trailers() {
+    let message = gix::objs::commit::MessageRef::from_bytes(
+      b"Title\n\nBody\n\nCo-authored-by: Alice <alice@example.com>\nSigned-off-by: Bob <bob@example.com>\nco-authored-by: Carol <carol@example.com>\n",
+    );
+
+    assert_eq!(
+      co_authors(&message),
+      vec!["Alice <alice@example.com>", "Carol <carol@example.com>"]
+    );
+  }
 }
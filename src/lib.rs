@@ -7,20 +7,30 @@
 
 use crate::app::handler::handle_key_events;
 use crate::app::{App, AppResult, Event, EventHandler};
+use crate::cli::{FetchCliArgs, LogCliArgs, ManifestCliArgs};
+use crate::filter::LogFilter;
+use crate::git::repo::{Repo, RepoStatus};
 use crate::manifest::Manifest;
+use crate::seen::Seen;
+use crate::semaphore::Semaphore;
 use crate::terminal::Tui;
 use crate::theme::ColorTheme;
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, mpsc};
 
 pub mod app;
 pub mod cli;
 pub mod config;
 pub mod difftool;
+pub mod filter;
+pub mod forge;
 pub mod git;
 pub mod manifest;
+pub mod output;
+pub mod seen;
 pub mod semaphore;
 pub mod terminal;
 pub mod theme;
@@ -47,9 +57,17 @@ pub fn clean(share_path: PathBuf, verbose: bool) -> AppResult<()> {
   Ok(())
 }
 
-pub fn diff(manifest: PathBuf, share_path: PathBuf, theme: ColorTheme) -> AppResult<()> {
-  let manifest = Manifest::new(manifest, share_path)?;
-  let mut app: App = App::from_manifest(manifest, theme);
+pub fn diff(
+  args: &ManifestCliArgs,
+  share_path: PathBuf,
+  state_path: PathBuf,
+  theme: ColorTheme,
+  first_weekday: chrono::Weekday,
+) -> AppResult<()> {
+  let seen = Seen::load(&state_path, &args.manifest)?;
+  let manifest = load_manifest(args, share_path, seen.last_run)?;
+  let mut app: App = App::from_manifest(manifest, seen, theme);
+  app.first_weekday = first_weekday;
 
   let backend = CrosstermBackend::new(std::io::stderr());
   let terminal = Terminal::new(backend)?;
@@ -63,19 +81,103 @@ pub fn diff(manifest: PathBuf, share_path: PathBuf, theme: ColorTheme) -> AppRes
 
     match tui.events.next()? {
       Event::Tick(sender) => app.tick(sender)?,
-      Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
+      Event::Key(key_event) => handle_key_events(key_event, &mut app, &tui.events.sender)?,
       Event::Mouse(_) => {}
       Event::Resize(_, _) => {}
       Event::RepoStatusChange(id, state) => app.update_repo_status(&id, state)?,
-      Event::RepoStatusComplete(id, logs) => app.update_repo_logs(&id, logs)?,
+      Event::RepoStatusComplete(id, history) => app.update_repo_logs(&id, history, &tui.events.sender)?,
+      Event::DifftoolComplete(result) => app.difftool_complete(result),
+      Event::TouchedComplete(id, path, touched) => app.update_touched(&id, &path, touched),
+      Event::ReadComplete(id, sha, read) => app.seen.update_read(&id, &sha, read),
+    }
+
+    if let Some(command) = app.take_terminal_difftool() {
+      let result = tui.suspend(|| difftool::run_in_terminal(command))?;
+      app.difftool_complete(result);
     }
   }
 
   tui.exit()?;
+  app.seen.save()?;
+
+  Ok(())
+}
 
+/// Clones or pulls each repo, printing a line as each changes status.
+/// Fails when any repo fails to update.
+/// Clones or pulls the selected remotes, without reading their logs, so
+/// neither the last run nor `since` and `until` are needed.
+pub fn fetch(args: &FetchCliArgs, share_path: PathBuf) -> AppResult<()> {
+  let mut manifest = Manifest::new(args.manifest_args.manifest.clone(), share_path, None)?;
+  manifest.retain_tags(&args.manifest_args.tags)?;
+  manifest.retain_ids(&args.only)?;
+
+  let repos = sync_repos(
+    &manifest,
+    |id, repo, root_path, sender, semaphore| repo.fetch(id, root_path, sender, semaphore),
+    |id, status| match status {
+      RepoStatus::Failed(reason) => eprintln!("failed: {id}, reason: {}", reason.join(": ")),
+      status => println!("{}: {id}", status.label()),
+    },
+  )?;
+
+  let failed = repos
+    .iter()
+    .filter(|(_id, repo)| matches!(repo.status, RepoStatus::Failed(_)))
+    .count();
+  if failed > 0 {
+    return Err(format!("{failed} of {} repos failed to update", repos.len()).into());
+  }
+  let noun = if repos.len() == 1 { "repo" } else { "repos" };
+  println!("fetched: {} {noun}", repos.len());
   Ok(())
 }
 
+pub fn log(args: &LogCliArgs, share_path: PathBuf, state_path: PathBuf) -> AppResult<()> {
+  let filter = LogFilter::new(args.author.clone(), args.grep.as_deref(), args.path.clone())?;
+  let last_run = Seen::load(&state_path, &args.manifest_args.manifest)?.last_run;
+  let manifest = load_manifest(&args.manifest_args, share_path, last_run)?;
+  let since = manifest.since_datetime.unwrap();
+
+  let mut repos = if !args.no_fetch {
+    update_repos(&manifest, |id, status| {
+      if let RepoStatus::Failed(reason) = status {
+        eprintln!("failed: {id}, reason: {}", reason.join(": "));
+      }
+    })?
+  } else {
+    read_repos(&manifest)
+  };
+
+  let offset_sec = chrono::Local::now().offset().local_minus_utc();
+  let offset = chrono::offset::FixedOffset::east_opt(offset_sec).unwrap();
+
+  for (id, repo) in &mut repos {
+    if repo.truncated {
+      eprintln!("truncated: {id}, at {} commits", repo.logs.len());
+    }
+    let touched = filter
+      .touched(manifest.root.as_ref().unwrap(), repo)
+      .inspect_err(|err| eprintln!("failed: {id}, reason: {err}"))
+      .ok();
+    let logs = std::mem::take(&mut repo.logs);
+    repo.logs = logs
+      .into_iter()
+      .filter(|log| filter.matches(repo, log, touched.as_ref()))
+      .collect();
+  }
+
+  let mut stdout = std::io::stdout().lock();
+  output::write(
+    &mut stdout,
+    &args.format,
+    &repos,
+    &since,
+    manifest.until_datetime.as_ref(),
+    &offset,
+  )
+}
+
 pub fn write_default_manifest(manifest_path: PathBuf) -> AppResult<()> {
   println!("Creating file: {:?}", manifest_path);
 
@@ -94,3 +196,122 @@ pub fn write_default_manifest(manifest_path: PathBuf) -> AppResult<()> {
   writeln!(file)?;
   Ok(())
 }
+
+/// Reads the manifest, narrowed to the remotes with `--tag` and with `since`
+/// and `until` replaced by `--since` and `--until`.
+fn load_manifest(
+  args: &ManifestCliArgs,
+  share_path: PathBuf,
+  last_run: Option<chrono::DateTime<chrono::Utc>>,
+) -> AppResult<Manifest> {
+  let mut manifest = Manifest::new(args.manifest.clone(), share_path, last_run)?;
+  manifest.retain_tags(&args.tags)?;
+  if let Some(since) = &args.since {
+    manifest.override_since(since, last_run)?;
+  }
+  if let Some(until) = &args.until {
+    manifest.override_until(until, last_run)?;
+  }
+  Ok(manifest)
+}
+
+/// Reads the logs of each repo already checked out to the local cache.
+fn read_repos(manifest: &Manifest) -> Vec<(String, Repo)> {
+  let root_path = manifest.root.as_ref().unwrap();
+  let since = manifest.since_datetime.unwrap();
+  let mut repos: Vec<(String, Repo)> = manifest
+    .remotes
+    .iter()
+    .map(|(id, remote)| {
+      let mut repo: Repo = remote.clone().into();
+      if let Err(err) = repo.migrate_legacy_path(root_path) {
+        log::error!("failed migrating clone: {id}, reason: {err}");
+      }
+      let limits = repo.log_limits(&since, manifest.margin(), manifest.max_commits);
+      match repo
+        .path(root_path)
+        .and_then(|path| git::logs(&path, repo.branch.as_deref(), repo.history, limits))
+      {
+        Ok(history) => {
+          repo.logs = history.logs;
+          repo.truncated = history.truncated;
+          repo.status = RepoStatus::Finished;
+        }
+        Err(err) => {
+          eprintln!("failed: {id}, reason: {err}");
+          repo.status = RepoStatus::failed(&*err);
+        }
+      }
+      (id.clone(), repo)
+    })
+    .collect();
+
+  let sort = manifest.repo_sort();
+  repos.sort_by(|(_, a), (_, b)| sort.compare(a, b));
+  repos
+}
+
+/// Clones or pulls each repo in the manifest without a terminal, blocking
+/// until every repo has either finished or failed.
+fn update_repos(
+  manifest: &Manifest,
+  on_status_change: impl FnMut(&str, &RepoStatus),
+) -> AppResult<Vec<(String, Repo)>> {
+  let since = manifest.since_datetime.unwrap();
+  sync_repos(
+    manifest,
+    |id, repo, root_path, sender, semaphore| {
+      let limits = repo.log_limits(&since, manifest.margin(), manifest.max_commits);
+      repo.update(id, root_path, limits, sender, semaphore)
+    },
+    on_status_change,
+  )
+}
+
+/// Starts `sync` for each repo in the manifest, then collects their status
+/// events until every repo has either finished or failed.
+fn sync_repos(
+  manifest: &Manifest,
+  sync: impl Fn(&str, &Repo, &Path, mpsc::Sender<Event>, Arc<Semaphore>) -> AppResult<()>,
+  mut on_status_change: impl FnMut(&str, &RepoStatus),
+) -> AppResult<Vec<(String, Repo)>> {
+  let root_path = manifest.root.as_ref().unwrap();
+  let semaphore = Arc::new(Semaphore::new(app::MAX_CONCURRENT_GIT_OPS));
+  let (sender, receiver) = mpsc::channel();
+
+  let mut repos: indexmap::IndexMap<String, Repo> = manifest
+    .remotes
+    .iter()
+    .map(|(id, remote)| (id.clone(), remote.clone().into()))
+    .collect();
+
+  for (id, repo) in &repos {
+    sync(id, repo, root_path, sender.clone(), Arc::clone(&semaphore))?;
+  }
+  drop(sender);
+
+  for event in receiver {
+    match event {
+      Event::RepoStatusChange(id, status) => {
+        on_status_change(&id, &status);
+        if let Some(repo) = repos.get_mut(&id) {
+          repo.status = status;
+        }
+      }
+      Event::RepoStatusComplete(id, history) => {
+        on_status_change(&id, &RepoStatus::Finished);
+        if let Some(repo) = repos.get_mut(&id) {
+          repo.logs = history.logs;
+          repo.truncated = history.truncated;
+          repo.status = RepoStatus::Finished;
+        }
+      }
+      _ => {}
+    }
+  }
+
+  let sort = manifest.repo_sort();
+  let mut repos: Vec<(String, Repo)> = repos.into_iter().collect();
+  repos.sort_by(|(_, a), (_, b)| sort.compare(a, b));
+  Ok(repos)
+}
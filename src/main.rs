@@ -20,15 +20,21 @@ fn main() -> AppResult<()> {
   let _ = setup_dyd_config_path()?;
   let share_path = setup_dyd_share_path()?;
   let state_path = setup_dyd_state_path()?;
-  setup_logger(state_path)?;
+  setup_logger(state_path.clone())?;
 
   match command {
     Command::Clean { verbose } => dyd::clean(share_path, verbose),
     Command::Diff(args) => {
-      let theme = args.theme.unwrap_or(config.theme.unwrap_or(Theme::Auto));
-      dyd::diff(args.manifest, share_path, theme.try_into()?)
+      let theme = args
+        .theme
+        .clone()
+        .unwrap_or(config.theme.unwrap_or(Theme::Auto));
+      let first_weekday = config.first_weekday.unwrap_or(chrono::Weekday::Sun);
+      dyd::diff(&args, share_path, state_path, theme.try_into()?, first_weekday)
     }
+    Command::Fetch(args) => dyd::fetch(&args, share_path),
     Command::Init(args) => dyd::write_default_manifest(args.manifest),
+    Command::Log(args) => dyd::log(&args, share_path, state_path),
   }
 }
 
@@ -1,9 +1,15 @@
 use crate::difftool::Difftool;
+use crate::forge::ForgeKind;
+use crate::git;
+use crate::git::repo::RepoSort;
 use crate::time;
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use indexmap::IndexMap;
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 #[derive(Debug)]
 struct ManifestParseError(String);
@@ -19,52 +25,631 @@ impl std::error::Error for ManifestParseError {}
 pub struct Manifest {
   #[serde(default = "default_difftool")]
   pub(crate) difftool: Difftool,
+  #[serde(default)]
+  pub(crate) difftool_shell: bool,
+  #[serde(default)]
+  pub(crate) difftool_terminal: bool,
+  #[serde(default)]
+  pub(crate) exclude_authors: Vec<String>,
+  #[serde(default)]
+  pub(crate) history: git::HistoryMode,
+  #[serde(default)]
+  pub(crate) include: Vec<PathBuf>,
+  #[serde(default = "default_margin_days")]
+  pub(crate) margin_days: u32,
+  #[serde(default = "default_max_commits")]
+  pub(crate) max_commits: usize,
   pub(crate) since: String,
   #[serde(skip)]
   pub(crate) since_datetime: Option<chrono::DateTime<chrono::Utc>>,
-  pub(crate) remotes: HashMap<String, Remote>,
+  /// The order of repos. Defaults to `group` when any remote has a group,
+  /// and otherwise to `recent`.
+  pub(crate) sort: Option<RepoSort>,
+  #[serde(default)]
+  pub(crate) remotes: IndexMap<String, Remote>,
   pub(crate) root: Option<PathBuf>,
+  /// Logs from this time on are dimmed, in the same forms as `since`. Named
+  /// days, ie `today` or `2026-10-14`, are included in full.
+  pub(crate) until: Option<String>,
+  #[serde(skip)]
+  pub(crate) until_datetime: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl Default for Manifest {
   fn default() -> Self {
     Self {
       difftool: Difftool::Git,
+      difftool_shell: false,
+      difftool_terminal: false,
+      exclude_authors: vec![],
+      history: git::HistoryMode::default(),
+      include: vec![],
+      margin_days: default_margin_days(),
+      max_commits: default_max_commits(),
       since: "1 week ago".to_string(),
       since_datetime: None,
-      remotes: HashMap::new(),
+      sort: None,
+      remotes: IndexMap::new(),
       root: None,
+      until: None,
+      until_datetime: None,
     }
   }
 }
 
 impl Manifest {
-  pub fn new(path: std::path::PathBuf, root: PathBuf) -> Result<Manifest, Box<dyn std::error::Error>> {
+  /// The order of repos, from `sort` or else from whether remotes are grouped.
+  pub fn repo_sort(&self) -> RepoSort {
+    match self.sort {
+      Some(sort) => sort,
+      None if self.remotes.values().any(|remote| remote.group.is_some()) => RepoSort::Group,
+      None => RepoSort::Recent,
+    }
+  }
+
+  /// How far before `since` to read logs.
+  pub fn margin(&self) -> chrono::Duration {
+    chrono::Duration::days(self.margin_days.into())
+  }
+
+  /// Reads the manifest at `path`. `last_run` is the time that `since = "last
+  /// run"` refers to.
+  pub fn new(
+    path: std::path::PathBuf,
+    root: PathBuf,
+    last_run: Option<chrono::DateTime<chrono::Utc>>,
+  ) -> Result<Manifest, Box<dyn std::error::Error>> {
     let manifest_contents =
       std::fs::read_to_string(&path).with_context(|| format!("Error reading file: `{}`", path.to_str().unwrap()))?;
 
     let mut manifest: Manifest = toml::from_str(&manifest_contents)?;
-    let since_datetime = time::parse_relative(&manifest.since, &chrono::Utc::now())?;
-    if let Difftool::Fallthrough(difftool) = &manifest.difftool
-      && difftool.is_empty()
-    {
+    let now = chrono::Utc::now();
+    let since_datetime = time::parse_since(&manifest.since, &now, last_run.as_ref())?;
+    let until_datetime = manifest
+      .until
+      .as_deref()
+      .map(|until| time::parse_until(until, &now, last_run.as_ref()))
+      .transpose()?;
+    validate_difftool(&manifest.difftool, "manifest")?;
+
+    let layer = Layer {
+      include: std::mem::take(&mut manifest.include),
+      remotes: std::mem::take(&mut manifest.remotes),
+    };
+    layer.merge_into(&path, &mut manifest.remotes, &mut vec![])?;
+
+    for (id, remote) in manifest.remotes.iter_mut() {
+      log::info!("remote `{id}` from {:?}", remote.source);
+      if remote.path.is_none() && remote.origin.is_empty() {
+        return Err(Box::new(ManifestParseError(format!(
+          "Remote `{id}` in {:?} must have either an origin or a path",
+          remote.source
+        ))));
+      }
+      remote.difftool_shell.get_or_insert(manifest.difftool_shell);
+      remote
+        .difftool_terminal
+        .get_or_insert(manifest.difftool_terminal);
+      remote.history.get_or_insert(manifest.history);
+      remote
+        .exclude_authors
+        .extend(manifest.exclude_authors.iter().cloned());
+      if let Some(difftool) = &remote.difftool {
+        validate_difftool(difftool, &format!("remote `{id}` in {:?}", remote.source))?;
+      }
+      if let Some(since) = &remote.since {
+        let datetime = time::parse_since(since, &now, last_run.as_ref()).map_err(|err| {
+          ManifestParseError(format!("Invalid since for remote `{id}` in {:?}: {err}", remote.source))
+        })?;
+        remote.since_datetime = Some(datetime);
+      }
+    }
+
+    validate_cache_paths(&manifest.remotes)?;
+    if manifest.max_commits == 0 {
       return Err(Box::new(ManifestParseError(
-        "When difftool is present in manifest, it must have length > 0".to_string(),
+        "When max_commits is present in manifest, it must be > 0".to_string(),
       )));
     }
+
+    validate_window(
+      &manifest.since,
+      since_datetime,
+      manifest.until.as_deref(),
+      until_datetime,
+    )?;
     manifest.root = Some(root);
     manifest.since_datetime = Some(since_datetime);
+    manifest.until_datetime = until_datetime;
     Ok(manifest)
   }
+
+  /// Replaces `since`, including any overrides on remotes.
+  pub fn override_since(
+    &mut self,
+    since: &str,
+    last_run: Option<chrono::DateTime<chrono::Utc>>,
+  ) -> Result<(), Box<dyn std::error::Error>> {
+    let since_datetime = time::parse_since(since, &chrono::Utc::now(), last_run.as_ref())?;
+    validate_window(since, since_datetime, self.until.as_deref(), self.until_datetime)?;
+    self.since = since.to_string();
+    self.since_datetime = Some(since_datetime);
+    for remote in self.remotes.values_mut() {
+      remote.since = None;
+      remote.since_datetime = None;
+    }
+    Ok(())
+  }
+
+  /// Replaces `until`.
+  pub fn override_until(
+    &mut self,
+    until: &str,
+    last_run: Option<chrono::DateTime<chrono::Utc>>,
+  ) -> Result<(), Box<dyn std::error::Error>> {
+    let until_datetime = time::parse_until(until, &chrono::Utc::now(), last_run.as_ref())?;
+    if let Some(since_datetime) = self.since_datetime {
+      validate_window(&self.since, since_datetime, Some(until), Some(until_datetime))?;
+    }
+    self.until = Some(until.to_string());
+    self.until_datetime = Some(until_datetime);
+    Ok(())
+  }
+
+  /// Keeps only the remotes with the given `ids`. Keeps every remote when no
+  /// ids are given.
+  pub fn retain_ids(&mut self, ids: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(unknown) = ids.iter().find(|id| !self.remotes.contains_key(*id)) {
+      return Err(Box::new(ManifestParseError(format!("No remote with id `{unknown}`"))));
+    }
+    if !ids.is_empty() {
+      self.remotes.retain(|id, _remote| ids.contains(id));
+    }
+    Ok(())
+  }
+
+  /// Keeps only the remotes with at least one of `tags`. Keeps every remote
+  /// when no tags are given.
+  pub fn retain_tags(&mut self, tags: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if tags.is_empty() {
+      return Ok(());
+    }
+    self
+      .remotes
+      .retain(|_id, remote| remote.tags.iter().any(|tag| tags.contains(tag)));
+    if self.remotes.is_empty() {
+      return Err(Box::new(ManifestParseError(format!(
+        "No remotes are tagged with {}",
+        tags.join(", ")
+      ))));
+    }
+    Ok(())
+  }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// A remote repository to clone and pull. `since`, `difftool` and `history`
+/// override the top-level manifest values for this remote only, while
+/// `exclude_authors` adds to those of the manifest. `group` lists the remote
+/// under a header in the Repos pane, and `tags` select remotes with `--tag`.
+///
+/// When `path` is given, the repository is read from an existing working
+/// copy instead of being cloned into the local cache. It is only fetched
+/// when `fetch` is set, and is never merged into.
+#[derive(Clone, Debug, Default, Deserialize)]
 pub struct Remote {
   pub(crate) name: String,
+  #[serde(default)]
   pub(crate) origin: String,
+  pub(crate) path: Option<PathBuf>,
+  #[serde(default)]
+  pub(crate) fetch: bool,
+  pub(crate) forge: Option<ForgeKind>,
+  pub(crate) forge_url: Option<String>,
+  pub(crate) group: Option<String>,
+  #[serde(default)]
+  pub(crate) tags: Vec<String>,
   pub(crate) branch: Option<String>,
+  #[serde(default, deserialize_with = "deserialize_color")]
+  pub(crate) color: Option<Color>,
+  pub(crate) difftool: Option<Difftool>,
+  pub(crate) difftool_shell: Option<bool>,
+  pub(crate) difftool_terminal: Option<bool>,
+  #[serde(default)]
+  pub(crate) exclude_authors: Vec<String>,
+  pub(crate) history: Option<git::HistoryMode>,
+  pub(crate) since: Option<String>,
+  #[serde(skip)]
+  pub(crate) since_datetime: Option<chrono::DateTime<chrono::Utc>>,
+  /// The manifest, or included file, that the remote was read from.
+  #[serde(skip)]
+  pub(crate) source: PathBuf,
+}
+
+/// The parts of a manifest that are layered through `include`. Included
+/// files may not have any other settings.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Layer {
+  #[serde(default)]
+  include: Vec<PathBuf>,
+  #[serde(default)]
+  remotes: IndexMap<String, Remote>,
+}
+
+impl Layer {
+  /// Adds the remotes of each included file to `remotes` in order, followed
+  /// by the remotes of this layer, read from `path`. Remotes replace those
+  /// with the same id from earlier files. `stack` holds the files being
+  /// included, to catch cycles.
+  fn merge_into(
+    self,
+    path: &Path,
+    remotes: &mut IndexMap<String, Remote>,
+    stack: &mut Vec<PathBuf>,
+  ) -> Result<(), Box<dyn std::error::Error>> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if stack.contains(&canonical) {
+      return Err(Box::new(ManifestParseError(format!(
+        "Manifest {path:?} includes itself"
+      ))));
+    }
+    stack.push(canonical);
+
+    let dir = path.parent().map(PathBuf::from).unwrap_or_default();
+    for include in &self.include {
+      let include = expand_path(include, &dir)?;
+      let contents = std::fs::read_to_string(&include)
+        .with_context(|| format!("Error reading file included from {path:?}: {include:?}"))?;
+      let layer: Layer = toml::from_str(&contents)
+        .map_err(|err| ManifestParseError(format!("Error parsing included file {include:?}: {err}")))?;
+      layer.merge_into(&include, remotes, stack)?;
+    }
+
+    for (id, mut remote) in self.remotes {
+      if let Some(local_path) = &remote.path {
+        remote.path = Some(expand_path(local_path, &dir)?);
+      }
+      remote.source = path.to_path_buf();
+      remotes.insert(id, remote);
+    }
+
+    stack.pop();
+    Ok(())
+  }
+}
+
+fn deserialize_color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  match Option::<String>::deserialize(deserializer)? {
+    Some(color) => Color::from_str(&color)
+      .map(Some)
+      .map_err(|_| serde::de::Error::custom(format!("unknown color `{color}`"))),
+    None => Ok(None),
+  }
+}
+
+/// Expands a leading `~` to the user's home directory, and resolves relative
+/// paths against the directory containing the manifest.
+fn expand_path(path: &Path, manifest_dir: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+  let path = match path.strip_prefix("~") {
+    Ok(rest) => {
+      let home = std::env::var("HOME").context("Unable to access HOME")?;
+      Path::new(&home).join(rest)
+    }
+    Err(_) => path.to_path_buf(),
+  };
+
+  if path.is_absolute() {
+    Ok(path)
+  } else {
+    Ok(manifest_dir.join(path))
+  }
+}
+
+/// Remotes cloned into the local cache must not share a directory.
+fn validate_cache_paths(remotes: &IndexMap<String, Remote>) -> Result<(), ManifestParseError> {
+  let mut ids: Vec<&String> = remotes.keys().collect();
+  ids.sort();
+
+  let mut seen: HashMap<PathBuf, &str> = HashMap::new();
+  for id in ids {
+    let remote = &remotes[id];
+    if remote.path.is_some() {
+      continue;
+    }
+    let Some(cache_path) = git::cache_path(&remote.origin) else {
+      return Err(ManifestParseError(format!(
+        "Unable to determine local path for remote `{id}` from origin `{}`",
+        remote.origin
+      )));
+    };
+    if let Some(other) = seen.insert(cache_path.clone(), id) {
+      return Err(ManifestParseError(format!(
+        "Remotes `{other}` and `{id}` would both be cloned to {cache_path:?}"
+      )));
+    }
+  }
+  Ok(())
+}
+
+/// `until` must come after `since`.
+fn validate_window(
+  since: &str,
+  since_datetime: chrono::DateTime<chrono::Utc>,
+  until: Option<&str>,
+  until_datetime: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<(), ManifestParseError> {
+  match until_datetime {
+    Some(until_datetime) if until_datetime <= since_datetime => Err(ManifestParseError(format!(
+      "until `{}` must be later than since `{since}`",
+      until.unwrap_or_default()
+    ))),
+    _ => Ok(()),
+  }
+}
+
+fn validate_difftool(difftool: &Difftool, source: &str) -> Result<(), ManifestParseError> {
+  if let Difftool::Fallthrough(difftool) = difftool
+    && difftool.is_empty()
+  {
+    return Err(ManifestParseError(format!(
+      "When difftool is present in {source}, it must have length > 0"
+    )));
+  }
+  if let Difftool::Fallthrough(difftool) = difftool
+    && let Err(err) = shell_words::split(difftool)
+  {
+    return Err(ManifestParseError(format!("Invalid difftool in {source}: {err}")));
+  }
+  Ok(())
 }
 
 fn default_difftool() -> Difftool {
   Difftool::Git
 }
+
+fn default_margin_days() -> u32 {
+  7
+}
+
+fn default_max_commits() -> usize {
+  5000
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn overrides_manifest() -> Result<Manifest, Box<dyn std::error::Error>> {
+    Manifest::new("fixtures/remote_overrides.toml".into(), "/tmp".into(), None)
+  }
+
+  #[test]
+  fn remote_overrides() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = overrides_manifest()?;
+
+    struct Expected {
+      id: &'static str,
+      color: Option<Color>,
+      difftool: Option<Difftool>,
+      difftool_terminal: bool,
+      exclude_authors: &'static [&'static str],
+      group: Option<&'static str>,
+      overrides_since: bool,
+    }
+    let cases = [
+      Expected {
+        id: "app",
+        color: Some(Color::Magenta),
+        difftool: Some(Difftool::GitHub),
+        difftool_terminal: true,
+        exclude_authors: &["Release Bot", "dependabot[bot]"],
+        group: Some("product"),
+        overrides_since: true,
+      },
+      Expected {
+        id: "infra",
+        color: None,
+        difftool: None,
+        difftool_terminal: false,
+        exclude_authors: &["dependabot[bot]"],
+        group: None,
+        overrides_since: false,
+      },
+    ];
+    for expected in cases {
+      let id = expected.id;
+      let remote = &manifest.remotes[id];
+      assert_eq!(remote.color, expected.color, "{id}");
+      assert_eq!(remote.difftool, expected.difftool, "{id}");
+      assert_eq!(remote.difftool_terminal, Some(expected.difftool_terminal), "{id}");
+      assert_eq!(remote.exclude_authors, expected.exclude_authors, "{id}");
+      assert_eq!(remote.group.as_deref(), expected.group, "{id}");
+      assert_eq!(
+        remote
+          .since_datetime
+          .is_some_and(|since| since > manifest.since_datetime.unwrap()),
+        expected.overrides_since,
+        "{id}"
+      );
+    }
+
+    Ok(())
+  }
+
+  #[test]
+  fn remote_tags() -> Result<(), Box<dyn std::error::Error>> {
+    let mut manifest = overrides_manifest()?;
+    manifest.retain_tags(&["web".into(), "mobile".into()])?;
+    let ids: Vec<&String> = manifest.remotes.keys().collect();
+    assert_eq!(ids, vec!["app"]);
+
+    let error = manifest.retain_tags(&["ops".into()]).unwrap_err();
+    assert_eq!(error.to_string(), "No remotes are tagged with ops");
+    Ok(())
+  }
+
+  #[test]
+  fn retain_ids() -> Result<(), Box<dyn std::error::Error>> {
+    let mut manifest = overrides_manifest()?;
+    manifest.retain_ids(&[])?;
+    assert_eq!(manifest.remotes.len(), 2);
+
+    manifest.retain_ids(&["infra".into()])?;
+    let ids: Vec<&String> = manifest.remotes.keys().collect();
+    assert_eq!(ids, vec!["infra"]);
+
+    let error = manifest.retain_ids(&["app".into()]).unwrap_err();
+    assert_eq!(error.to_string(), "No remote with id `app`");
+    Ok(())
+  }
+
+  #[test]
+  fn override_since() -> Result<(), Box<dyn std::error::Error>> {
+    let mut manifest = overrides_manifest()?;
+    manifest.override_since("2026-10-01", None)?;
+    assert_eq!(manifest.since, "2026-10-01");
+    assert!(
+      manifest
+        .remotes
+        .values()
+        .all(|remote| remote.since_datetime.is_none())
+    );
+
+    assert!(manifest.override_since("someday", None).is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn until() -> Result<(), Box<dyn std::error::Error>> {
+    let mut manifest = overrides_manifest()?;
+    assert!(manifest.until_datetime.unwrap() > manifest.since_datetime.unwrap());
+
+    manifest.override_until("today", None)?;
+    assert_eq!(manifest.until.as_deref(), Some("today"));
+    assert!(manifest.until_datetime.unwrap() > chrono::Utc::now());
+
+    let error = manifest.override_until("2 months ago", None).unwrap_err();
+    assert!(error.is::<ManifestParseError>());
+    assert_eq!(
+      error.to_string(),
+      "until `2 months ago` must be later than since `1 month ago`"
+    );
+    assert_eq!(manifest.until.as_deref(), Some("today"));
+
+    manifest.override_until("2 days ago", None)?;
+    let error = manifest.override_since("yesterday", None).unwrap_err();
+    assert!(error.is::<ManifestParseError>());
+    assert_eq!(
+      error.to_string(),
+      "until `2 days ago` must be later than since `yesterday`"
+    );
+    assert_eq!(manifest.since, "1 month ago");
+    Ok(())
+  }
+
+  #[test]
+  fn remote_path() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = Manifest::new("fixtures/path.toml".into(), "/tmp".into(), None)?;
+    let home = std::env::var("HOME")?;
+
+    let local = &manifest.remotes["local"];
+    assert_eq!(local.path, Some(Path::new(&home).join("src/dyd")));
+    assert!(local.fetch);
+
+    let relative = &manifest.remotes["relative"];
+    assert_eq!(relative.path, Some(PathBuf::from("fixtures/../dyd")));
+    assert!(!relative.fetch);
+
+    Ok(())
+  }
+
+  #[test]
+  fn include_layers_remotes() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = Manifest::new("fixtures/include.toml".into(), "/tmp".into(), None)?;
+
+    let app = &manifest.remotes["app"];
+    assert_eq!(app.name, "App (personal branch)");
+    assert_eq!(app.source, PathBuf::from("fixtures/include.toml"));
+
+    let infra = &manifest.remotes["infra"];
+    assert_eq!(infra.source, PathBuf::from("fixtures/include/../include/team.toml"));
+    assert_eq!(infra.path, Some(PathBuf::from("fixtures/include/../include/infra")));
+
+    Ok(())
+  }
+
+  #[test]
+  fn remotes_in_manifest_order() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = Manifest::new("fixtures/include.toml".into(), "/tmp".into(), None)?;
+    let ids: Vec<&String> = manifest.remotes.keys().collect();
+    assert_eq!(ids, vec!["app", "infra", "personal"]);
+    assert_eq!(manifest.repo_sort(), RepoSort::Recent);
+
+    let manifest = overrides_manifest()?;
+    assert_eq!(manifest.repo_sort(), RepoSort::Group);
+    Ok(())
+  }
+
+  #[test]
+  fn include_only() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = Manifest::new("fixtures/include_only.toml".into(), "/tmp".into(), None)?;
+    let ids: Vec<&String> = manifest.remotes.keys().collect();
+    assert_eq!(ids, vec!["app", "infra"]);
+    Ok(())
+  }
+
+  #[test]
+  fn include_with_settings() {
+    let error = Manifest::new("fixtures/invalid_include_settings.toml".into(), "/tmp".into(), None).unwrap_err();
+    assert!(error.is::<ManifestParseError>());
+    let message = error.to_string();
+    assert!(
+      message.starts_with("Error parsing included file \"fixtures/include/settings.toml\""),
+      "{message}"
+    );
+    assert!(message.contains("unknown field `since`"), "{message}");
+  }
+
+  #[test]
+  fn include_cycle() {
+    let error = Manifest::new("fixtures/invalid_include_cycle.toml".into(), "/tmp".into(), None).unwrap_err();
+    assert!(error.is::<ManifestParseError>());
+    assert!(error.to_string().ends_with("includes itself"));
+  }
+
+  #[test]
+  fn remote_without_origin_or_path() {
+    let error = Manifest::new("fixtures/invalid_remote_path.toml".into(), "/tmp".into(), None).unwrap_err();
+    assert!(error.is::<ManifestParseError>());
+  }
+
+  #[test]
+  fn remote_cache_path_collision() {
+    let error = Manifest::new("fixtures/invalid_collision.toml".into(), "/tmp".into(), None).unwrap_err();
+    assert!(error.is::<ManifestParseError>());
+    assert_eq!(
+      error.to_string(),
+      "Remotes `dyd` and `dyd_git` would both be cloned to \"github.com/synchronal/dyd\""
+    );
+  }
+
+  #[test]
+  fn remote_invalid_difftool() {
+    let error = Manifest::new("fixtures/invalid_remote_difftool.toml".into(), "/tmp".into(), None).unwrap_err();
+    assert!(error.is::<ManifestParseError>());
+  }
+
+  #[test]
+  fn difftool_unbalanced_quotes() {
+    let error = Manifest::new("fixtures/invalid_difftool_quotes.toml".into(), "/tmp".into(), None).unwrap_err();
+    assert!(error.is::<ManifestParseError>());
+    assert!(
+      error
+        .to_string()
+        .starts_with("Invalid difftool in manifest")
+    );
+  }
+}
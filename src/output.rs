@@ -0,0 +1,193 @@
+//! Non-interactive rendering of repo logs, for use in scripts and pipes.
+
+use crate::app::AppResult;
+use crate::git::repo::{Log, Repo};
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(clap::ValueEnum, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+  /// Human-readable text, grouped by repo.
+  #[default]
+  Text,
+  /// One JSON object per commit, separated by newlines.
+  Json,
+  /// Markdown, with one section per repo.
+  Markdown,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonLog<'a> {
+  repo: &'a str,
+  name: &'a str,
+  branch: Option<&'a str>,
+  sha: &'a str,
+  author: &'a str,
+  commit_datetime: String,
+  message: &'a str,
+}
+
+/// Writes the logs of each repo newer than `since`, unless the repo overrides
+/// `since` for itself, and no newer than `until` to `out`.
+pub fn write(
+  out: &mut impl Write,
+  format: &Format,
+  repos: &[(String, Repo)],
+  since: &chrono::DateTime<chrono::Utc>,
+  until: Option<&chrono::DateTime<chrono::Utc>>,
+  timezone_offset: &chrono::offset::FixedOffset,
+) -> AppResult<()> {
+  for (id, repo) in repos {
+    let logs: Vec<&Log> = repo
+      .logs
+      .iter()
+      .filter(|log| repo.in_window(log, since, until))
+      .collect();
+
+    match format {
+      Format::Text => write_text(out, repo, &logs, timezone_offset)?,
+      Format::Json => write_json(out, id, repo, &logs)?,
+      Format::Markdown => write_markdown(out, repo, &logs, timezone_offset)?,
+    }
+  }
+  Ok(())
+}
+
+fn write_text(
+  out: &mut impl Write,
+  repo: &Repo,
+  logs: &[&Log],
+  timezone_offset: &chrono::offset::FixedOffset,
+) -> AppResult<()> {
+  if logs.is_empty() {
+    return Ok(());
+  }
+  writeln!(out, "{repo}")?;
+  for log in logs {
+    writeln!(
+      out,
+      "  {}  {}  {}  {}",
+      log.sha,
+      log
+        .commit_datetime
+        .with_timezone(timezone_offset)
+        .format("%a %b %d %R"),
+      log.author,
+      log.message.trim_end()
+    )?;
+  }
+  writeln!(out)?;
+  Ok(())
+}
+
+fn write_json(out: &mut impl Write, id: &str, repo: &Repo, logs: &[&Log]) -> AppResult<()> {
+  for log in logs {
+    let line = JsonLog {
+      repo: id,
+      name: &repo.name,
+      branch: repo.branch.as_deref(),
+      sha: &log.sha,
+      author: &log.author,
+      commit_datetime: log.commit_datetime.to_rfc3339(),
+      message: log.message.trim_end(),
+    };
+    writeln!(out, "{}", serde_json::to_string(&line)?)?;
+  }
+  Ok(())
+}
+
+fn write_markdown(
+  out: &mut impl Write,
+  repo: &Repo,
+  logs: &[&Log],
+  timezone_offset: &chrono::offset::FixedOffset,
+) -> AppResult<()> {
+  if logs.is_empty() {
+    return Ok(());
+  }
+  writeln!(out, "## {repo}")?;
+  writeln!(out)?;
+  for log in logs {
+    writeln!(
+      out,
+      "- `{}` {} — {} ({})",
+      log.sha,
+      log.message.trim_end(),
+      log.author,
+      log
+        .commit_datetime
+        .with_timezone(timezone_offset)
+        .format("%a %b %d %R")
+    )?;
+  }
+  writeln!(out)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn repos() -> Vec<(String, Repo)> {
+    let repo = Repo {
+      branch: Some("main".into()),
+      logs: vec![
+        Log {
+          author: "Alice".into(),
+          commit_datetime: chrono::DateTime::from_timestamp(2000, 0).unwrap(),
+          // Titles of single-line messages end with their newline.
+          message: "Add \"thing\"\n".into(),
+          sha: "abc1234".into(),
+          ..Default::default()
+        },
+        Log {
+          author: "Bob".into(),
+          commit_datetime: chrono::DateTime::from_timestamp(500, 0).unwrap(),
+          message: "Old".into(),
+          sha: "def5678".into(),
+          ..Default::default()
+        },
+      ],
+      name: "DYD".into(),
+      origin: "git@github.com:synchronal/dyd".into(),
+      ..Default::default()
+    };
+    vec![("dyd".into(), repo)]
+  }
+
+  fn render(format: Format) -> String {
+    let since = chrono::DateTime::from_timestamp(1000, 0).unwrap();
+    let utc = chrono::offset::FixedOffset::east_opt(0).unwrap();
+    let mut out = Vec::new();
+    write(&mut out, &format, &repos(), &since, None, &utc).unwrap();
+    String::from_utf8(out).unwrap()
+  }
+
+  #[test]
+  fn write_text_skips_logs_before_since() {
+    assert_eq!(
+      render(Format::Text),
+      "DYD — (main)\n  abc1234  Thu Jan 01 00:33  Alice  Add \"thing\"\n\n"
+    );
+  }
+
+  #[test]
+  fn write_json_lines() {
+    assert_eq!(
+      render(Format::Json),
+      concat!(
+        r#"{"repo":"dyd","name":"DYD","branch":"main","sha":"abc1234","author":"Alice","#,
+        r#""commit_datetime":"1970-01-01T00:33:20+00:00","message":"Add \"thing\""}"#,
+        "\n"
+      )
+    );
+  }
+
+  #[test]
+  fn write_markdown_sections() {
+    assert_eq!(
+      render(Format::Markdown),
+      "## DYD — (main)\n\n- `abc1234` Add \"thing\" — Alice (Thu Jan 01 00:33)\n\n"
+    );
+  }
+}
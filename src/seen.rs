@@ -0,0 +1,252 @@
+//! The newest commit seen in each repo, and when dyd was last run, saved per
+//! manifest under `~/.local/state/dyd/seen`.
+
+use crate::app::{AppResult, Event};
+use crate::git;
+use crate::git::repo::{Log, Repo};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+
+/// A commit that has been viewed or marked read. Everything reachable from it
+/// is read.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SeenLog {
+  #[serde(default)]
+  pub sha: String,
+  /// The commit time, in seconds since the epoch.
+  pub timestamp: i64,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct SeenFile {
+  last_run: Option<i64>,
+  #[serde(default)]
+  repos: BTreeMap<String, SeenLog>,
+}
+
+#[derive(Debug, Default)]
+pub struct Seen {
+  /// When dyd was last run with this manifest, before the current run.
+  pub last_run: Option<chrono::DateTime<chrono::Utc>>,
+  /// Where to save, or `None` to keep the state in memory.
+  path: Option<PathBuf>,
+  /// The full shas of the logs of each repo reachable from its seen commit.
+  /// Repos without them, until they are found or when the seen commit is no
+  /// longer in the repo, are read by commit time instead.
+  read: HashMap<String, HashSet<String>>,
+  /// The newest commit seen in each repo, by repo id.
+  repos: BTreeMap<String, SeenLog>,
+  started: chrono::DateTime<chrono::Utc>,
+}
+
+impl Seen {
+  /// Reads what has been seen for the manifest at `manifest_path`. A missing
+  /// file means that nothing has been seen yet.
+  pub fn load(state_path: &Path, manifest_path: &Path) -> AppResult<Seen> {
+    let path = state_path.join("seen").join(file_name(manifest_path));
+    let file: SeenFile = match std::fs::read_to_string(&path) {
+      Ok(contents) => toml::from_str(&contents)?,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => SeenFile::default(),
+      Err(err) => return Err(Box::new(err)),
+    };
+    Ok(Seen {
+      last_run: file
+        .last_run
+        .and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0)),
+      path: Some(path),
+      read: HashMap::new(),
+      repos: file.repos,
+      started: chrono::Utc::now(),
+    })
+  }
+
+  /// Saves the commits seen, recording the current run as the last run.
+  pub fn save(&self) -> AppResult<()> {
+    let Some(path) = &self.path else {
+      return Ok(());
+    };
+    let file = SeenFile {
+      last_run: Some(self.started.timestamp()),
+      repos: self.repos.clone(),
+    };
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, toml::to_string(&file)?)?;
+    Ok(())
+  }
+
+  /// Whether `log` is not reachable from the commit seen in the repo `id`.
+  pub fn is_unread(&self, id: &str, log: &Log) -> bool {
+    let Some(seen) = self.repos.get(id) else {
+      return true;
+    };
+    match self.read.get(id) {
+      Some(read) => !read.contains(&log.full_sha),
+      None => log.commit_datetime.timestamp() > seen.timestamp,
+    }
+  }
+
+  /// Marks `log` and everything reachable from it in the repo `id` as read,
+  /// returning whether anything changed. A log older than the seen commit
+  /// only reads as seen until the repo's logs are next updated, so that the
+  /// newer commit stays seen.
+  pub fn mark(&mut self, id: &str, log: &Log) -> bool {
+    if !self.is_unread(id, log) {
+      return false;
+    }
+    let timestamp = log.commit_datetime.timestamp();
+    match (self.repos.get(id), self.read.get_mut(id)) {
+      (Some(seen), Some(read)) if timestamp < seen.timestamp => {
+        read.insert(log.full_sha.clone());
+      }
+      _ => {
+        self.read.remove(id);
+        self.repos.insert(
+          id.to_string(),
+          SeenLog {
+            sha: log.full_sha.clone(),
+            timestamp,
+          },
+        );
+      }
+    }
+    true
+  }
+
+  /// Finds the logs of `repo` reachable from the commit seen in it on a
+  /// background thread, sending them with the sha they were found from as
+  /// [`Event::ReadComplete`]. Does nothing when nothing has been seen.
+  pub fn spawn_read(
+    &self,
+    id: &str,
+    root_path: &Path,
+    repo: &Repo,
+    limits: git::LogLimits,
+    sender: mpsc::Sender<Event>,
+  ) {
+    let Some(sha) = self.repos.get(id).map(|seen| seen.sha.clone()) else {
+      return;
+    };
+    let repo_path = match repo.path(root_path) {
+      Ok(repo_path) => repo_path,
+      Err(err) => {
+        log::error!("failed finding read logs: {id}, reason: {err}");
+        return;
+      }
+    };
+    let id = id.to_string();
+    thread::spawn(move || match git::reachable(&repo_path, &sha, limits) {
+      Ok(read) => {
+        if let Err(err) = sender.send(Event::ReadComplete(id, sha, read)) {
+          log::error!("failed sending read logs: {err}");
+        }
+      }
+      Err(err) => log::error!("failed finding read logs: {id}, reason: {err}"),
+    });
+  }
+
+  /// Records the logs of the repo `id` reachable from `sha`, unless another
+  /// commit has been seen since they were requested. `None` means that `sha`
+  /// is no longer in the repo.
+  pub fn update_read(&mut self, id: &str, sha: &str, read: Option<HashSet<String>>) {
+    if self.repos.get(id).is_none_or(|seen| seen.sha != sha) {
+      return;
+    }
+    match read {
+      Some(read) => self.read.insert(id.to_string(), read),
+      None => self.read.remove(id),
+    };
+  }
+}
+
+/// The manifest's canonical path, flattened into a file name.
+fn file_name(manifest_path: &Path) -> String {
+  let path = manifest_path
+    .canonicalize()
+    .unwrap_or_else(|_| manifest_path.to_path_buf());
+  let name: String = path
+    .to_string_lossy()
+    .trim_start_matches('/')
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect();
+  format!("{name}.toml")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn log(sha: &str, seconds: i64) -> Log {
+    Log {
+      commit_datetime: chrono::DateTime::from_timestamp(seconds, 0).unwrap(),
+      full_sha: sha.into(),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn seen_marks_newer_logs_only() {
+    let mut seen = Seen::default();
+    assert!(seen.is_unread("dyd", &log("b", 200)));
+
+    assert!(seen.mark("dyd", &log("b", 200)));
+    assert!(!seen.mark("dyd", &log("a", 100)));
+    assert!(!seen.is_unread("dyd", &log("a", 100)));
+    assert!(seen.is_unread("dyd", &log("c", 300)));
+    assert!(seen.is_unread("other", &log("a", 100)));
+  }
+
+  #[test]
+  fn seen_reads_by_reachability() {
+    let mut seen = Seen::default();
+    seen.mark("dyd", &log("b", 300));
+    assert!(!seen.is_unread("dyd", &log("a", 100)));
+
+    // `c` is older than `b`, but was merged after `b` was seen.
+    seen.update_read("dyd", "b", Some(HashSet::from(["a".into(), "b".into()])));
+    assert!(!seen.is_unread("dyd", &log("a", 100)));
+    assert!(seen.is_unread("dyd", &log("c", 200)));
+
+    assert!(seen.mark("dyd", &log("c", 200)));
+    assert!(!seen.is_unread("dyd", &log("c", 200)));
+    assert_eq!(seen.repos["dyd"].sha, "b");
+
+    seen.update_read("dyd", "stale", None);
+    assert!(seen.is_unread("dyd", &log("d", 250)));
+    seen.update_read("dyd", "b", None);
+    assert!(!seen.is_unread("dyd", &log("d", 250)));
+  }
+
+  #[test]
+  fn seen_saves_per_manifest() -> AppResult<()> {
+    let state_path = std::env::temp_dir().join(format!("dyd-seen-{}", std::process::id()));
+    let manifest_path = Path::new("fixtures/remote_overrides.toml");
+
+    let mut seen = Seen::load(&state_path, manifest_path)?;
+    assert_eq!(seen.last_run, None);
+    seen.mark("app", &log("b", 200));
+    seen.save()?;
+
+    let loaded = Seen::load(&state_path, manifest_path)?;
+    assert_eq!(
+      loaded.last_run.map(|time| time.timestamp()),
+      Some(seen.started.timestamp())
+    );
+    assert!(!loaded.is_unread("app", &log("a", 100)));
+    assert!(Seen::load(&state_path, Path::new("fixtures/path.toml"))?.is_unread("app", &log("a", 100)));
+
+    std::fs::remove_dir_all(state_path)?;
+    Ok(())
+  }
+}
@@ -52,6 +52,26 @@ impl<B: Backend> Tui<B> {
     }
   }
 
+  /// Leaves the alternate screen while `f` runs, ie for a difftool that draws
+  /// in the terminal, and restores it afterwards.
+  pub fn suspend<T>(&mut self, f: impl FnOnce() -> T) -> AppResult<T>
+  where
+    <B as Backend>::Error: 'static,
+  {
+    let _paused = self.events.pause();
+    terminal::disable_raw_mode()?;
+    crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+    self.terminal.show_cursor().map_err(Box::new)?;
+
+    let result = f();
+
+    terminal::enable_raw_mode()?;
+    crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+    self.terminal.hide_cursor().map_err(Box::new)?;
+    self.terminal.clear().map_err(Box::new)?;
+    Ok(result)
+  }
+
   /// Exits the terminal interface.
   ///
   /// It disables the raw mode and reverts back the terminal properties.
@@ -34,11 +34,25 @@ fn detect_colortheme() -> Result<ColorTheme, Box<dyn std::error::Error>> {
 #[derive(Debug)]
 pub struct ColorTheme {
   pub border_color: Color,
+  /// Shades of days in the calendar, from no commits to the most.
+  pub calendar_activity_colors: [Color; 5],
+  /// The numbers of days shaded by activity.
+  pub calendar_activity_text_color: Color,
+  /// Days in the range selected with `v`.
+  pub calendar_range_style: Style,
+  /// The day under the cursor.
+  pub calendar_selected_style: Style,
+  pub diff_added_color: Color,
   pub diff_age_color: Color,
   pub diff_author_color: Color,
   pub diff_message_color: Color,
+  /// Rows in the range marked with `v`.
+  pub diff_range_style: Style,
+  pub diff_removed_color: Color,
+  pub diff_repo_color: Color,
   pub diff_row_hightlight_style: Style,
   pub diff_sha_color: Color,
+  pub error_color: Color,
   pub header_selected_color: Color,
   pub help_header_style: Style,
   pub help_text_style: Style,
@@ -49,11 +63,29 @@ pub struct ColorTheme {
 fn dark_theme() -> ColorTheme {
   ColorTheme {
     border_color: Color::LightCyan,
+    calendar_activity_colors: [
+      Color::DarkGray,
+      Color::Rgb(14, 68, 41),
+      Color::Rgb(0, 109, 50),
+      Color::Rgb(38, 166, 65),
+      Color::Rgb(57, 211, 83),
+    ],
+    calendar_activity_text_color: Color::Black,
+    calendar_range_style: Style::default().bg(Color::Gray).fg(Color::Black),
+    calendar_selected_style: Style::default()
+      .bg(Color::LightRed)
+      .fg(Color::Black)
+      .add_modifier(Modifier::BOLD),
+    diff_added_color: Color::LightGreen,
     diff_age_color: Color::Red,
     diff_author_color: Color::Yellow,
     diff_message_color: Color::White,
+    diff_range_style: Style::default().bg(Color::DarkGray),
+    diff_removed_color: Color::LightRed,
+    diff_repo_color: Color::LightGreen,
     diff_row_hightlight_style: Style::default().add_modifier(Modifier::BOLD),
     diff_sha_color: Color::LightCyan,
+    error_color: Color::LightRed,
     header_selected_color: Color::Red,
     help_header_style: Style::default().fg(Color::Gray).add_modifier(Modifier::DIM),
     help_text_style: Style::default().fg(Color::Cyan),
@@ -65,13 +97,31 @@ fn dark_theme() -> ColorTheme {
 fn light_theme() -> ColorTheme {
   ColorTheme {
     border_color: Color::Cyan,
+    calendar_activity_colors: [
+      Color::Gray,
+      Color::Rgb(155, 233, 168),
+      Color::Rgb(64, 196, 99),
+      Color::Rgb(48, 161, 78),
+      Color::Rgb(33, 110, 57),
+    ],
+    calendar_activity_text_color: Color::Black,
+    calendar_range_style: Style::default().bg(Color::DarkGray).fg(Color::White),
+    calendar_selected_style: Style::default()
+      .bg(Color::Red)
+      .fg(Color::White)
+      .add_modifier(Modifier::BOLD),
+    diff_added_color: Color::Green,
     diff_age_color: Color::Red,
     diff_author_color: Color::Blue,
     diff_message_color: Color::Black,
+    diff_range_style: Style::default().bg(Color::Gray),
+    diff_removed_color: Color::Red,
+    diff_repo_color: Color::Green,
     diff_row_hightlight_style: Style::default()
       .add_modifier(Modifier::UNDERLINED)
       .add_modifier(Modifier::BOLD),
     diff_sha_color: Color::Magenta,
+    error_color: Color::Red,
     header_selected_color: Color::Red,
     help_header_style: Style::default()
       .fg(Color::Black)
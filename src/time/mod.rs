@@ -1,6 +1,18 @@
 //! Functions for parsing time in different formats.
 pub use relative::parse_relative;
+pub use since::{parse_since, parse_until};
 pub use unix::parse_unix;
 
 mod relative;
+mod since;
 mod unix;
+
+#[derive(Debug)]
+struct TimeParseError(String);
+
+impl std::fmt::Display for TimeParseError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+impl std::error::Error for TimeParseError {}
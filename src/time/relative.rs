@@ -1,3 +1,4 @@
+use super::TimeParseError;
 use crate::app::AppResult;
 
 use chrono::prelude::*;
@@ -6,23 +7,15 @@ use std::sync::OnceLock;
 
 static PATTERN: OnceLock<Regex> = OnceLock::new();
 
-#[derive(Debug)]
-struct TimeParseError(String);
-
-impl std::fmt::Display for TimeParseError {
-  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-    write!(f, "{}", self.0)
-  }
-}
-impl std::error::Error for TimeParseError {}
-
 /// Transforms a description of relative time to a DateTime in UTC.
 ///
 /// Relative times can be described in the following formats:
 ///
+/// - `6 hours ago`
 /// - `1 day ago`
 /// - `3 days ago`
 /// - `1 week ago`
+/// - `2 months ago`
 ///
 pub fn parse_relative(string: &str, base: &DateTime<Utc>) -> AppResult<DateTime<Utc>> {
   let pattern = PATTERN.get_or_init(|| Regex::new(r"^(?P<amount>\d+) (?P<unit>\w+) ago$").unwrap());
@@ -38,11 +31,13 @@ fn compute_relative(captures: regex::Captures, base: &DateTime<Utc>) -> AppResul
     let amount: i64 = captures.name("amount").unwrap().as_str().parse::<i64>()?;
 
     match captures.name("unit").unwrap().as_str() {
-      "day" | "days" => chrono::Duration::try_days(amount).unwrap(),
+      "hour" | "hours" => chrono::Duration::try_hours(amount),
+      "day" | "days" => chrono::Duration::try_days(amount),
       "month" | "months" => return relative_months(base, amount),
-      "week" | "weeks" => chrono::Duration::try_weeks(amount).unwrap(),
+      "week" | "weeks" => chrono::Duration::try_weeks(amount),
       other => return Err(Box::new(TimeParseError(format!("Unknown unit {other}")))),
     }
+    .ok_or_else(|| TimeParseError(format!("{amount} is too large")))?
   };
 
   match base.checked_sub_signed(duration) {
@@ -52,56 +47,10 @@ fn compute_relative(captures: regex::Captures, base: &DateTime<Utc>) -> AppResul
 }
 
 fn relative_months(base: &DateTime<Utc>, amount: i64) -> AppResult<DateTime<Utc>> {
-  let current_month = base.month0();
-  let new_month = (current_month as i64) - amount;
-
-  if new_month < 0 {
-    let current_year = base.year();
-    let amount = (new_month + 1).abs();
-    relative_months(
-      &base
-        .with_year(current_year - 1)
-        .unwrap()
-        .with_month(12)
-        .unwrap(),
-      amount,
-    )
-  } else {
-    match base.with_month0(new_month as u32) {
-      Some(datetime) => Ok(datetime),
-      None => {
-        let year = base.year();
-        let last_day = get_days_from_month(year, (new_month + 1) as u32);
-
-        Ok(
-          base
-            .with_day(1)
-            .unwrap()
-            .with_month0(new_month as u32)
-            .unwrap()
-            .with_day(last_day as u32)
-            .unwrap(),
-        )
-      }
-    }
-  }
-}
-
-fn get_days_from_month(year: i32, month: u32) -> i64 {
-  NaiveDate::from_ymd_opt(
-    match month {
-      12 => year + 1,
-      _ => year,
-    },
-    match month {
-      12 => 1,
-      _ => month + 1,
-    },
-    1,
-  )
-  .unwrap()
-  .signed_duration_since(NaiveDate::from_ymd_opt(year, month, 1).unwrap())
-  .num_days()
+  u32::try_from(amount)
+    .ok()
+    .and_then(|amount| base.checked_sub_months(chrono::Months::new(amount)))
+    .ok_or_else(|| TimeParseError(format!("{amount} is too large")).into())
 }
 
 #[cfg(test)]
@@ -233,6 +182,13 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn parse_relative_hours_ok() {
+    let now = Utc.with_ymd_and_hms(2022, 4, 26, 16, 11, 21).unwrap();
+    let time = super::parse_relative("18 hours ago", &now).unwrap();
+    assert_eq!(time, Utc.with_ymd_and_hms(2022, 4, 25, 22, 11, 21).unwrap());
+  }
+
   #[test]
   fn parse_relative_error() {
     let error = super::parse_relative("hello", &Utc::now()).unwrap_err();
@@ -241,4 +197,13 @@ mod tests {
     let error = super::parse_relative("2 moons ago", &Utc::now()).unwrap_err();
     assert!(error.is::<super::TimeParseError>());
   }
+
+  #[test]
+  fn parse_relative_too_large() {
+    let now = Utc::now();
+    for unit in ["hours", "days", "weeks", "months"] {
+      let string = format!("{} {unit} ago", i64::MAX);
+      assert!(super::parse_relative(&string, &now).is_err());
+    }
+  }
 }
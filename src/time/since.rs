@@ -0,0 +1,212 @@
+use super::{TimeParseError, parse_relative};
+use crate::app::AppResult;
+
+use chrono::prelude::*;
+use regex::Regex;
+use std::sync::OnceLock;
+
+static BUSINESS_DAYS: OnceLock<Regex> = OnceLock::new();
+
+/// The forms accepted by [`parse_since`], for error messages.
+const ACCEPTED: &str = "`N hours ago`, `N days ago`, `N business days ago`, `N weeks ago`, \
+  `N months ago`, `today`, `yesterday`, `last <weekday>`, `YYYY-MM-DD` or `last run`";
+
+/// Transforms a description of when to show logs from to a DateTime in UTC.
+///
+/// Along with the relative times of [`parse_relative`], accepts:
+///
+/// - `2026-10-01`, `today`, `yesterday` and `last friday`, from the start of
+///   the day in the local timezone
+/// - `3 business days ago`, skipping weekends
+/// - `last run` or `since last run`, the time dyd was last run, or a week
+///   before `base` when it never has been
+///
+pub fn parse_since(string: &str, base: &DateTime<Utc>, last_run: Option<&DateTime<Utc>>) -> AppResult<DateTime<Utc>> {
+  parse_since_in(string, base, last_run, &Local)
+}
+
+/// Like [`parse_since`], except that days are included in full, ie `today`
+/// and `2026-10-14` are the end of that day rather than the start.
+pub fn parse_until(string: &str, base: &DateTime<Utc>, last_run: Option<&DateTime<Utc>>) -> AppResult<DateTime<Utc>> {
+  parse_bound_in(string, base, last_run, &Local, Bound::End)
+}
+
+/// Which end of a day that is named, rather than relative, to resolve to.
+#[derive(Clone, Copy, PartialEq)]
+enum Bound {
+  Start,
+  End,
+}
+
+fn parse_since_in<Tz: TimeZone>(
+  string: &str,
+  base: &DateTime<Utc>,
+  last_run: Option<&DateTime<Utc>>,
+  tz: &Tz,
+) -> AppResult<DateTime<Utc>> {
+  parse_bound_in(string, base, last_run, tz, Bound::Start)
+}
+
+fn parse_bound_in<Tz: TimeZone>(
+  string: &str,
+  base: &DateTime<Utc>,
+  last_run: Option<&DateTime<Utc>>,
+  tz: &Tz,
+  bound: Bound,
+) -> AppResult<DateTime<Utc>> {
+  let day = |date: NaiveDate| match bound {
+    Bound::Start => start_of_day(date, tz),
+    Bound::End => start_of_day(date + chrono::Days::new(1), tz),
+  };
+  let lowercase = string.trim().to_lowercase();
+  let since = lowercase.strip_prefix("since ").unwrap_or(&lowercase);
+  let today = base.with_timezone(tz).date_naive();
+
+  match since {
+    "last run" => {
+      return Ok(
+        last_run
+          .copied()
+          .unwrap_or(*base - chrono::Duration::weeks(1)),
+      );
+    }
+    "today" => return day(today),
+    "yesterday" => return day(today - chrono::Days::new(1)),
+    _ => {}
+  }
+  if let Some(weekday) = since.strip_prefix("last ")
+    && let Ok(weekday) = weekday.parse::<Weekday>()
+  {
+    return day(last_weekday(today, weekday));
+  }
+  if let Ok(date) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+    return day(date);
+  }
+  let business_days = BUSINESS_DAYS.get_or_init(|| Regex::new(r"^(?P<amount>\d+) business days? ago$").unwrap());
+  if let Some(captures) = business_days.captures(since) {
+    let amount = captures.name("amount").unwrap().as_str().parse::<u32>()?;
+    return Ok(business_days_ago(base, amount, tz));
+  }
+
+  parse_relative(since, base)
+    .map_err(|err| TimeParseError(format!("Unable to parse `{string}`: {err}. Expected one of {ACCEPTED}")).into())
+}
+
+/// The most recent `weekday` before `today`, ie a week ago when today is
+/// that weekday.
+fn last_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+  let days = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+  let days = if days == 0 { 7 } else { days };
+  today - chrono::Days::new(days.into())
+}
+
+/// Steps back from `base` one day at a time, counting only weekdays.
+fn business_days_ago<Tz: TimeZone>(base: &DateTime<Utc>, amount: u32, tz: &Tz) -> DateTime<Utc> {
+  let mut time = base.with_timezone(tz);
+  let mut remaining = amount;
+  while remaining > 0 {
+    time -= chrono::Duration::days(1);
+    if !matches!(time.weekday(), Weekday::Sat | Weekday::Sun) {
+      remaining -= 1;
+    }
+  }
+  time.with_timezone(&Utc)
+}
+
+fn start_of_day<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> AppResult<DateTime<Utc>> {
+  tz.from_local_datetime(&date.and_time(NaiveTime::MIN))
+    .earliest()
+    .map(|time| time.with_timezone(&Utc))
+    .ok_or_else(|| TimeParseError(format!("Unable to find the start of {date}")).into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(string: &str) -> AppResult<DateTime<Utc>> {
+    // A Monday morning.
+    let now = Utc.with_ymd_and_hms(2026, 10, 19, 9, 30, 0).unwrap();
+    let last_run = Utc.with_ymd_and_hms(2026, 10, 16, 17, 0, 0).unwrap();
+    parse_since_in(string, &now, Some(&last_run), &Utc)
+  }
+
+  #[test]
+  fn parse_since_days() {
+    assert_eq!(
+      parse("today").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 19, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+      parse("Yesterday").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+      parse("2026-10-01").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+      parse("last friday").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+      parse("last mon").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 12, 0, 0, 0).unwrap()
+    );
+  }
+
+  #[test]
+  fn parse_since_business_days() {
+    assert_eq!(
+      parse("1 business day ago").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap()
+    );
+    assert_eq!(
+      parse("6 business days ago").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 9, 9, 30, 0).unwrap()
+    );
+  }
+
+  #[test]
+  fn parse_since_relative_and_last_run() {
+    assert_eq!(
+      parse("2 hours ago").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 19, 7, 30, 0).unwrap()
+    );
+    assert_eq!(
+      parse("since last run").unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 16, 17, 0, 0).unwrap()
+    );
+
+    let now = Utc.with_ymd_and_hms(2026, 10, 19, 9, 30, 0).unwrap();
+    assert_eq!(
+      parse_since_in("last run", &now, None, &Utc).unwrap(),
+      Utc.with_ymd_and_hms(2026, 10, 12, 9, 30, 0).unwrap()
+    );
+  }
+
+  #[test]
+  fn parse_until_includes_named_days() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 19, 9, 30, 0).unwrap();
+    let until = |string| parse_bound_in(string, &now, None, &Utc, Bound::End).unwrap();
+    assert_eq!(until("today"), Utc.with_ymd_and_hms(2026, 10, 20, 0, 0, 0).unwrap());
+    assert_eq!(
+      until("2026-10-14"),
+      Utc.with_ymd_and_hms(2026, 10, 15, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+      until("2 hours ago"),
+      Utc.with_ymd_and_hms(2026, 10, 19, 7, 30, 0).unwrap()
+    );
+  }
+
+  #[test]
+  fn parse_since_error_lists_accepted_forms() {
+    let error = parse("2 moons ago").unwrap_err();
+    assert!(error.is::<TimeParseError>());
+    assert_eq!(
+      error.to_string(),
+      format!("Unable to parse `2 moons ago`: Unknown unit moons. Expected one of {ACCEPTED}")
+    );
+  }
+}
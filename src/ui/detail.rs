@@ -0,0 +1,106 @@
+use crate::app::{App, CommitDetail};
+use crate::git::repo::FileChange;
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+
+pub fn render<'a>(app: &'a App, detail: &'a CommitDetail) -> Paragraph<'a> {
+  let container = Block::default()
+    .title(Span::styled(" Commit ", Style::default().add_modifier(Modifier::BOLD)))
+    .borders(Borders::ALL)
+    .style(Style::default().fg(app.theme.border_color));
+
+  let log = &detail.log;
+  let label = Style::default().fg(app.theme.text_color);
+  let author = Style::default().fg(app.theme.diff_author_color);
+  let message = Style::default().fg(app.theme.diff_message_color);
+
+  let mut lines = vec![
+    Line::from(vec![
+      Span::styled("commit    ", label),
+      Span::styled(log.full_sha.as_str(), Style::default().fg(app.theme.diff_sha_color)),
+    ]),
+    Line::from(vec![
+      Span::styled("Author    ", label),
+      Span::styled(log.author.as_str(), author),
+    ]),
+    Line::from(vec![
+      Span::styled("Committer ", label),
+      Span::styled(log.committer.as_str(), author),
+    ]),
+  ];
+  for co_author in &log.co_authors {
+    lines.push(Line::from(vec![
+      Span::styled("Co-author ", label),
+      Span::styled(co_author.as_str(), author),
+    ]));
+  }
+  lines.push(Line::from(vec![
+    Span::styled("Date      ", label),
+    Span::styled(
+      log
+        .commit_datetime
+        .with_timezone(&app.timezone_offset)
+        .format("%a %b %d %R %Y")
+        .to_string(),
+      Style::default().fg(app.theme.diff_age_color),
+    ),
+  ]));
+
+  lines.push(Line::default());
+  lines.push(Line::from(Span::styled(
+    log.message.as_str(),
+    message.add_modifier(Modifier::BOLD),
+  )));
+  if !log.body.is_empty() {
+    lines.push(Line::default());
+    lines.extend(
+      log
+        .body
+        .lines()
+        .map(|line| Line::from(Span::styled(line, message))),
+    );
+  }
+
+  lines.push(Line::default());
+  match &detail.changes {
+    Ok(changes) => lines.extend(changes.iter().map(|change| file_change(change, app))),
+    Err(reason) => lines.push(Line::from(Span::styled(
+      format!("Unable to read changed files: {reason}"),
+      Style::default().fg(app.theme.error_color),
+    ))),
+  }
+
+  Paragraph::new(lines)
+    .block(container)
+    .wrap(Wrap { trim: false })
+    .scroll((detail.scroll, 0))
+}
+
+fn file_change<'a>(change: &'a FileChange, app: &App) -> Line<'a> {
+  let mut spans = if change.binary {
+    vec![Span::styled(
+      format!("{:>13}", "binary"),
+      Style::default().fg(app.theme.text_color),
+    )]
+  } else {
+    vec![
+      Span::styled(
+        format!("{:>6}", format!("+{}", change.added)),
+        Style::default().fg(app.theme.diff_added_color),
+      ),
+      Span::raw(" "),
+      Span::styled(
+        format!("{:>6}", format!("-{}", change.removed)),
+        Style::default().fg(app.theme.diff_removed_color),
+      ),
+    ]
+  };
+  spans.push(Span::raw("  "));
+  spans.push(Span::styled(
+    change.path.as_str(),
+    Style::default().fg(app.theme.diff_message_color),
+  ));
+  Line::from(spans)
+}
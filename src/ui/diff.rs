@@ -1,9 +1,10 @@
-use crate::app::{App, SelectedPane};
+use crate::app::{App, LogRow, SelectedPane};
+use crate::git::repo::Repo;
 
 use ratatui::layout::Constraint;
 use ratatui::style::{Modifier, Style};
 use ratatui::text;
-use ratatui::widgets::{Block, Borders, Cell, Row, Table};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap};
 
 pub fn render(app: &App) -> Table<'_> {
   let container = Block::default()
@@ -11,33 +12,56 @@ pub fn render(app: &App) -> Table<'_> {
     .borders(Borders::ALL)
     .style(Style::default().fg(app.theme.border_color));
 
-  let mut rows: Vec<Row> = vec![];
-
-  if let Some(index) = app.repo_state.selected() {
-    let (_id, selected_repo) = app.repos.get_index(index).unwrap();
-    rows = selected_repo
-      .logs
-      .iter()
-      .map(|log| {
-        let stale = app.since >= log.commit_datetime;
-
-        let cells = [
-          Cell::from(sha(&log.sha, app)),
-          Cell::from(age(&log.commit_datetime, app)),
-          Cell::from(author(&log.author, app)),
-          Cell::from(message(&log.message, app)),
-        ];
-
-        Row::new(cells).style(stale_style(stale))
-      })
-      .collect()
+  let logs = app.visible_logs();
+  let range = marked_range(app, &logs);
+  let unread: Vec<bool> = logs
+    .iter()
+    .map(|row| app.seen.is_unread(row.id, row.log))
+    .collect();
+  let rows: Vec<Row> = logs
+    .into_iter()
+    .enumerate()
+    .map(|(index, row)| {
+      let LogRow { repo, log, .. } = row;
+      let stale = !app.in_window(repo, log);
+
+      let mut cells = vec![
+        Cell::from(sha(&log.sha, app)),
+        Cell::from(age(&log.commit_datetime, app)),
+      ];
+      if app.timeline {
+        cells.push(Cell::from(repo_name(repo, app)));
+      }
+      cells.push(Cell::from(author(&log.author, app)));
+      cells.push(Cell::from(text::Line::from(vec![
+        merge_marker(&row, app),
+        message(&log.message, app),
+      ])));
+
+      let mut style = stale_style(stale);
+      if unread[index] {
+        style = style.add_modifier(Modifier::BOLD);
+      }
+      if range
+        .as_ref()
+        .is_some_and(|(id, range)| *id == row.id && range.contains(&index))
+      {
+        style = style.patch(app.theme.diff_range_style);
+      }
+      // A gap separates the unread logs of a repo from those already seen.
+      let last_unread = !app.timeline && unread[index] && unread.get(index + 1) == Some(&false);
+      Row::new(cells)
+        .style(style)
+        .bottom_margin(last_unread.into())
+    })
+    .collect();
+
+  let mut widths = vec![Constraint::Length(9), Constraint::Length(17)];
+  if app.timeline {
+    widths.push(Constraint::Percentage(15));
   }
-  let widths = [
-    Constraint::Length(9),
-    Constraint::Length(17),
-    Constraint::Percentage(20),
-    Constraint::Percentage(100),
-  ];
+  widths.push(Constraint::Percentage(20));
+  widths.push(Constraint::Percentage(100));
 
   Table::new(rows, widths)
     .block(container)
@@ -46,11 +70,84 @@ pub fn render(app: &App) -> Table<'_> {
     .column_spacing(2)
 }
 
-fn title(app: &App) -> text::Span<'_> {
+/// The repo id and the indexes of the rows from the log marked with `v` to
+/// the selected log.
+fn marked_range<'a>(app: &'a App, logs: &[LogRow]) -> Option<(&'a str, std::ops::RangeInclusive<usize>)> {
+  let (id, sha) = app.range_mark.as_ref()?;
+  let marked = logs
+    .iter()
+    .position(|row| row.id == id && &row.log.full_sha == sha)?;
+  let selected = app.selected_repo_state.selected().unwrap_or(marked);
+  Some((id, marked.min(selected)..=marked.max(selected)))
+}
+
+/// Describes why the selected repo failed to update, in place of its logs.
+pub fn render_failure<'a>(app: &'a App, reason: &'a [String]) -> Paragraph<'a> {
+  let container = Block::default()
+    .title(title(app))
+    .borders(Borders::ALL)
+    .style(Style::default().fg(app.theme.border_color));
+
+  let error_style = Style::default().fg(app.theme.error_color);
+  let mut lines = vec![text::Line::from(text::Span::styled(
+    "Unable to update repo",
+    error_style.add_modifier(Modifier::BOLD),
+  ))];
+  for (index, cause) in reason.iter().enumerate() {
+    let prefix = if index == 0 { "  " } else { "  caused by: " };
+    lines.push(text::Line::from(text::Span::styled(
+      format!("{prefix}{cause}"),
+      error_style,
+    )));
+  }
+  lines.push(text::Line::default());
+  lines.push(text::Line::from(text::Span::styled(
+    "Press R to retry this repo.",
+    Style::default().fg(app.theme.text_color),
+  )));
+
+  Paragraph::new(lines)
+    .block(container)
+    .wrap(Wrap { trim: false })
+}
+
+fn title(app: &App) -> text::Line<'_> {
   let text_style = Style::default()
     .fg(super::selected_color(app, SelectedPane::Diff))
     .add_modifier(Modifier::BOLD);
-  text::Span::styled(" Diff ", text_style)
+  let mut text = if app.timeline {
+    " Diff — All repos ".to_string()
+  } else {
+    " Diff ".to_string()
+  };
+  if let Some(until) = &app.until {
+    let until = until
+      .with_timezone(&app.timezone_offset)
+      .format("%a %b %d %R");
+    text.push_str(&format!("— until {until} "));
+  }
+  if truncated(app) {
+    text.push_str(&format!("(truncated at {} commits) ", app.max_commits));
+  }
+  let mut spans = vec![text::Span::styled(text, text_style)];
+  if let Some(reason) = &app.difftool_failure {
+    let reason = reason.lines().next().unwrap_or_default();
+    spans.push(text::Span::styled(
+      format!("difftool failed: {reason} "),
+      Style::default().fg(app.theme.error_color),
+    ));
+  }
+  text::Line::from(spans)
+}
+
+fn truncated(app: &App) -> bool {
+  if app.timeline {
+    app.repos.values().any(|repo| repo.truncated)
+  } else {
+    app
+      .selected_repo()
+      .is_some_and(|(_id, repo)| repo.truncated)
+  }
 }
 
 fn age<'a>(datetime: &'a chrono::DateTime<chrono::Utc>, app: &App) -> text::Span<'a> {
@@ -71,6 +168,27 @@ fn message<'a>(text: &'a str, app: &'a App) -> text::Span<'a> {
   text::Span::styled(text, text_style)
 }
 
+fn repo_name<'a>(repo: &'a Repo, app: &'a App) -> text::Span<'a> {
+  let text_style = Style::default().fg(repo.color.unwrap_or(app.theme.diff_repo_color));
+  text::Span::styled(&repo.name, text_style)
+}
+
+fn merge_marker<'a>(row: &LogRow, app: &App) -> text::Span<'a> {
+  let marker = if row.nested {
+    "  ↳ "
+  } else if row.log.merged.is_empty() {
+    ""
+  } else if app
+    .expanded
+    .contains(&(row.id.to_string(), row.log.sha.clone()))
+  {
+    "▾ "
+  } else {
+    "▸ "
+  };
+  text::Span::styled(marker, Style::default().fg(app.theme.diff_message_color))
+}
+
 fn sha<'a>(text: &'a str, app: &'a App) -> text::Span<'a> {
   let text_style = Style::default().fg(app.theme.diff_sha_color);
   text::Span::styled(text, text_style)
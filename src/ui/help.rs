@@ -29,19 +29,114 @@ pub fn render(app: &App) -> Paragraph<'_> {
       Span::raw(" — "),
       Span::raw("page backwards"),
     ]),
+    Line::from(vec![
+      Span::raw(" a     "),
+      Span::raw(" — "),
+      Span::raw("toggle all repos"),
+    ]),
+    Line::from(vec![
+      Span::raw(" m M   "),
+      Span::raw(" — "),
+      Span::raw("mark read / mark all read"),
+    ]),
+    Line::from(vec![
+      Span::raw(" /     "),
+      Span::raw(" — "),
+      Span::raw("filter by author:, path:, message"),
+    ]),
+    Line::from(vec![
+      Span::raw(" ⏎     "),
+      Span::raw(" — "),
+      Span::raw("show / hide commit detail"),
+    ]),
+    Line::from(vec![
+      Span::raw(" ⏎     "),
+      Span::raw(" — "),
+      Span::raw("repos: collapse / expand group"),
+    ]),
+    Line::from(vec![
+      Span::raw(" S     "),
+      Span::raw(" — "),
+      Span::raw("cycle repo sort"),
+    ]),
+    Line::from(vec![
+      Span::raw(" JK    "),
+      Span::raw(" — "),
+      Span::raw("scroll commit detail"),
+    ]),
+    Line::from(vec![
+      Span::raw(" e     "),
+      Span::raw(" — "),
+      Span::raw("expand / collapse merge"),
+    ]),
     Line::from(vec![
       Span::raw(" d     "),
       Span::raw(" — "),
       Span::raw("open git difftool"),
     ]),
+    Line::from(vec![
+      Span::raw(" D     "),
+      Span::raw(" — "),
+      Span::raw("diff only the selected commit"),
+    ]),
+    Line::from(vec![
+      Span::raw(" v     "),
+      Span::raw(" — "),
+      Span::raw("mark / unmark the start of a range"),
+    ]),
+    Line::from(vec![
+      Span::raw(" o O   "),
+      Span::raw(" — "),
+      Span::raw("open commit / branch in browser"),
+    ]),
+    Line::from(vec![
+      Span::raw(" [ ]   "),
+      Span::raw(" — "),
+      Span::raw("builtin diff: previous / next file"),
+    ]),
+    Line::from(vec![
+      Span::raw(" n N   "),
+      Span::raw(" — "),
+      Span::raw("builtin diff: next / previous hunk"),
+    ]),
     Line::from(vec![Span::raw(" r     "), Span::raw(" — "), Span::raw("refresh repos")]),
+    Line::from(vec![
+      Span::raw(" R     "),
+      Span::raw(" — "),
+      Span::raw("refresh selected repo"),
+    ]),
     Line::from(vec![Span::raw("   ")]),
     Line::from(vec![
       Span::raw(" s     "),
       Span::raw(" — "),
       Span::raw("open / close calendar"),
     ]),
-    Line::from(vec![Span::raw(" <enter>"), Span::raw(" — "), Span::raw("select date")]),
+    Line::from(vec![
+      Span::raw(" <enter>"),
+      Span::raw(" — "),
+      Span::raw("select date, or range of dates"),
+    ]),
+    Line::from(vec![
+      Span::raw(" v     "),
+      Span::raw(" — "),
+      Span::raw("start / stop selecting a range"),
+    ]),
+    Line::from(vec![
+      Span::raw(" [ ]   "),
+      Span::raw(" — "),
+      Span::raw("previous / next month"),
+    ]),
+    Line::from(vec![Span::raw(" t     "), Span::raw(" — "), Span::raw("go to today")]),
+    Line::from(vec![
+      Span::raw(" a     "),
+      Span::raw(" — "),
+      Span::raw("shade days by all repos / selected repo"),
+    ]),
+    Line::from(vec![
+      Span::raw(" u U   "),
+      Span::raw(" — "),
+      Span::raw("dim logs after date / clear"),
+    ]),
     Line::from(vec![Span::raw(" <esc>  "), Span::raw(" — "), Span::raw("close modal")]),
     Line::from(vec![Span::raw("   ")]),
     Line::from(vec![Span::raw(" q <esc>"), Span::raw(" — "), Span::raw("quit")]),
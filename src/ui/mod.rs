@@ -1,10 +1,13 @@
 use crate::app::{App, SelectedPane};
 use ratatui::style::Color;
 
+pub mod detail;
 pub mod diff;
 pub mod help;
 pub mod modal;
+pub mod patch;
 pub mod repos;
+pub mod search;
 
 pub fn selected_color(app: &App, pane: SelectedPane) -> Color {
   if pane == app.selected_pane {
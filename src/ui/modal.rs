@@ -9,22 +9,41 @@ pub fn render(app: &App, frame: &mut Frame) {
   let window = frame.area();
 
   match app.modal {
-    SelectedModal::None => {}
+    SelectedModal::None | SelectedModal::Search => {}
     SelectedModal::Calendar => {
-      let x = std::cmp::max(window.width / 2 - 12, 0);
-      let y = std::cmp::max(window.height / 2 - 10, 0);
-      let background = Rect::new(x, y, 28, 12);
+      // Wide enough for a year of weeks beside the month, when there is room.
+      let width = window
+        .width
+        .saturating_sub(4)
+        .clamp(28, 28 + 2 + 3 + 2 * 53);
+      let x = (window.width.saturating_sub(width)) / 2;
+      let y = (window.height / 2).saturating_sub(10);
+      let background = Rect::new(x, y, width, 13).intersection(window);
       let area = background.inner(Margin {
         vertical: 1,
         horizontal: 2,
       });
 
+      let mut title = match app.selected_repo() {
+        Some((_id, repo)) if !app.activity_all_repos => format!(" Calendar — {} ", repo.name),
+        _ => " Calendar — all repos ".to_string(),
+      };
+      if app.calendar_state.is_range() {
+        title.push_str("— range ");
+      }
       let container = Block::default()
-        .title(" Calendar ")
+        .title(title)
         .borders(Borders::ALL)
         .style(Style::default().fg(app.theme.border_color));
 
-      let calendar = Calendar::new().block(container);
+      let calendar = Calendar::new()
+        .block(container)
+        .first_weekday(app.first_weekday)
+        .activity(app.activity())
+        .activity_colors(app.theme.calendar_activity_colors)
+        .activity_text_color(app.theme.calendar_activity_text_color)
+        .range_style(app.theme.calendar_range_style)
+        .selected_style(app.theme.calendar_selected_style);
 
       frame.render_widget(Clear, background);
       frame.render_stateful_widget(calendar, area, &mut app.calendar_state.clone());
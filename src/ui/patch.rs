@@ -0,0 +1,81 @@
+use crate::app::{App, DiffView, SelectedPane};
+use crate::git::patch::PatchLine;
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+pub fn render<'a>(app: &'a App, view: &'a DiffView, height: u16) -> Paragraph<'a> {
+  let container = Block::default()
+    .title(title(app, view))
+    .borders(Borders::ALL)
+    .style(Style::default().fg(app.theme.border_color));
+
+  let lines = match &view.patch {
+    Ok(patch) if patch.lines.is_empty() => vec![Line::from(Span::styled(
+      "No changes",
+      Style::default().fg(app.theme.text_color),
+    ))],
+    Ok(patch) => patch
+      .lines
+      .iter()
+      .skip(view.scroll)
+      .take(height.saturating_sub(2).into())
+      .map(|line| patch_line(line, app))
+      .collect(),
+    Err(reason) => vec![Line::from(Span::styled(
+      format!("Unable to read diff: {reason}"),
+      Style::default().fg(app.theme.error_color),
+    ))],
+  };
+
+  Paragraph::new(lines).block(container)
+}
+
+fn title<'a>(app: &App, view: &DiffView) -> Span<'a> {
+  let text_style = Style::default()
+    .fg(super::selected_color(app, SelectedPane::Diff))
+    .add_modifier(Modifier::BOLD);
+  let mut text = format!(" Diff {}..{} ", view.ref_from, view.ref_to);
+  if let Ok(patch) = &view.patch {
+    let files = patch.files();
+    let current = files.iter().filter(|start| **start <= view.scroll).count();
+    text.push_str(&format!("— file {current}/{} ", files.len()));
+  }
+  Span::styled(text, text_style)
+}
+
+fn patch_line<'a>(line: &'a PatchLine, app: &App) -> Line<'a> {
+  match line {
+    PatchLine::File(path) => Line::from(Span::styled(
+      path.as_str(),
+      Style::default()
+        .fg(app.theme.diff_sha_color)
+        .add_modifier(Modifier::BOLD),
+    )),
+    PatchLine::Hunk(header) => Line::from(Span::styled(
+      header.as_str(),
+      Style::default()
+        .fg(app.theme.text_color)
+        .add_modifier(Modifier::DIM),
+    )),
+    PatchLine::Context(text) => Line::from(Span::styled(
+      format!(" {text}"),
+      Style::default().fg(app.theme.diff_message_color),
+    )),
+    PatchLine::Added(text) => Line::from(Span::styled(
+      format!("+{text}"),
+      Style::default().fg(app.theme.diff_added_color),
+    )),
+    PatchLine::Removed(text) => Line::from(Span::styled(
+      format!("-{text}"),
+      Style::default().fg(app.theme.diff_removed_color),
+    )),
+    PatchLine::Binary => Line::from(Span::styled(
+      "Binary file",
+      Style::default()
+        .fg(app.theme.text_color)
+        .add_modifier(Modifier::DIM),
+    )),
+  }
+}
@@ -1,5 +1,5 @@
-use crate::app::{App, SelectedPane};
-use crate::git::repo::Repo;
+use crate::app::{App, RepoRow, SelectedPane};
+use crate::git::repo::{Repo, RepoStatus};
 
 use ratatui::layout::Constraint;
 use ratatui::style::{Modifier, Style};
@@ -12,13 +12,28 @@ pub fn render(app: &App) -> Table<'_> {
     .borders(Borders::ALL)
     .style(Style::default().fg(app.theme.border_color));
 
-  let rows = app.repos.iter().map(|(_id, repo)| {
-    let repo_name = text::Span::styled(repo.to_string(), Style::default().fg(app.theme.text_color));
-    let cells = [status_icon(repo, app), Cell::from(repo_name)];
-    Row::new(cells)
+  let rows = app.repo_rows().into_iter().map(|row| match row {
+    RepoRow::Group { name, collapsed, repos } => group_header(name, collapsed, &repos, app),
+    RepoRow::Repo { id, repo, grouped } => {
+      let indent = if grouped { "  " } else { "" };
+      let repo_name = text::Span::styled(
+        format!("{indent}{repo}"),
+        Style::default().fg(repo.color.unwrap_or(app.theme.text_color)),
+      );
+      let cells = [
+        status_icon(repo, app),
+        Cell::from(repo_name),
+        unread(app.unread_count(id, repo), app),
+      ];
+      Row::new(cells)
+    }
   });
 
-  let widths = [Constraint::Length(2), Constraint::Percentage(100)];
+  let widths = [
+    Constraint::Length(2),
+    Constraint::Percentage(100),
+    Constraint::Length(4),
+  ];
 
   Table::new(rows, widths)
     .block(container)
@@ -32,12 +47,55 @@ fn title(app: &App) -> text::Span<'_> {
   let text_style = Style::default()
     .fg(super::selected_color(app, SelectedPane::Repos))
     .add_modifier(Modifier::BOLD);
-  text::Span::styled(" Repos ", text_style)
+  text::Span::styled(format!(" Repos — {} ", app.sort), text_style)
+}
+
+/// A group's name and the number of logs between `since` and `until` in its
+/// repos.
+fn group_header<'a>(name: &str, collapsed: bool, repos: &[(&String, &Repo)], app: &App) -> Row<'a> {
+  let marker = if collapsed { "▸" } else { "▾" };
+  let commits: usize = repos
+    .iter()
+    .map(|(_id, repo)| {
+      repo
+        .logs
+        .iter()
+        .filter(|log| app.in_window(repo, log) && !repo.excludes(log))
+        .count()
+    })
+    .sum();
+  let unread_count = repos
+    .iter()
+    .map(|(id, repo)| app.unread_count(id, repo))
+    .sum();
+
+  let style = Style::default()
+    .fg(app.theme.text_color)
+    .add_modifier(Modifier::BOLD);
+  Row::new([
+    Cell::from(text::Span::styled(format!(" {marker}"), style)),
+    Cell::from(text::Span::styled(format!("{name} ({commits})"), style)),
+    unread(unread_count, app),
+  ])
+}
+
+/// The number of logs that have not been seen, when there are any.
+fn unread<'a>(count: usize, app: &App) -> Cell<'a> {
+  match count {
+    0 => Cell::default(),
+    count => Cell::from(text::Span::styled(
+      format!("{count:>4}"),
+      Style::default()
+        .fg(app.theme.text_color)
+        .add_modifier(Modifier::BOLD),
+    )),
+  }
 }
 
 fn status_icon<'a>(repo: &'a Repo, app: &'a App) -> Cell<'a> {
-  Cell::from(text::Span::styled(
-    repo.status.to_string(),
-    Style::default().fg(app.theme.text_color),
-  ))
+  let color = match repo.status {
+    RepoStatus::Failed(_) => app.theme.error_color,
+    _ => app.theme.text_color,
+  };
+  Cell::from(text::Span::styled(repo.status.to_string(), Style::default().fg(color)))
 }
@@ -0,0 +1,37 @@
+use crate::app::{App, SelectedModal};
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+/// The `/` prompt while a search is being typed, or else the filter applied
+/// to the Diff pane.
+pub fn render(app: &App) -> Paragraph<'_> {
+  let editing = app.modal == SelectedModal::Search;
+  let title = if editing { " Search " } else { " Filter " };
+  let container = Block::default()
+    .title(Span::styled(title, Style::default().add_modifier(Modifier::BOLD)))
+    .borders(Borders::ALL)
+    .style(Style::default().fg(app.theme.border_color));
+
+  let text_style = Style::default().fg(app.theme.text_color);
+  let hint_style = text_style.add_modifier(Modifier::DIM);
+  let mut spans = vec![Span::styled("/", hint_style)];
+  if editing {
+    spans.push(Span::styled(app.search.as_str(), text_style));
+    spans.push(Span::styled("▏", text_style));
+    match &app.search_error {
+      Some(reason) => spans.push(Span::styled(
+        format!("  {reason}"),
+        Style::default().fg(app.theme.error_color),
+      )),
+      None if app.search.is_empty() => spans.push(Span::styled("author:<name> path:<dir> <message regex>", hint_style)),
+      None => {}
+    }
+  } else {
+    spans.push(Span::styled(app.filter.to_string(), text_style));
+    spans.push(Span::styled("  (/ to edit, <esc> to clear)", hint_style));
+  }
+
+  Paragraph::new(Line::from(spans)).block(container)
+}
@@ -1,15 +1,51 @@
-use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc, Weekday};
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::widgets::{Block, StatefulWidget, Widget};
+use std::collections::HashMap;
 
-#[derive(Debug, Default, Clone, Eq, PartialEq)]
+/// The width of a month of days, ie `Su Mo Tu We Th Fr Sa` with a space on
+/// each side.
+const MONTH_WIDTH: u16 = 22;
+/// The fewest weeks worth showing in the multi-week view.
+const MIN_WEEKS: u16 = 8;
+/// The most weeks shown in the multi-week view, ie a year.
+const MAX_WEEKS: u16 = 53;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Calendar<'a> {
+  /// The number of commits on each day, when shading days by activity.
+  activity: Option<HashMap<NaiveDate, usize>>,
+  /// Shades for days with no commits, followed by increasing activity, from
+  /// the theme.
+  activity_colors: [Color; 5],
+  /// The color of the numbers of shaded days, from the theme.
+  activity_text_color: Color,
   block: Option<Block<'a>>,
+  first_weekday: Weekday,
+  /// Days in the selected range, from the theme.
+  range_style: Style,
+  /// The day under the cursor, from the theme.
+  selected_style: Style,
   style: Style,
 }
 
+impl Default for Calendar<'_> {
+  fn default() -> Self {
+    Self {
+      activity: None,
+      activity_colors: [Color::Reset; 5],
+      activity_text_color: Color::Reset,
+      block: None,
+      first_weekday: Weekday::Sun,
+      range_style: Style::default(),
+      selected_style: Style::default(),
+      style: Style::default(),
+    }
+  }
+}
+
 impl<'a> Calendar<'a> {
   pub fn new() -> Self {
     Self::default()
@@ -19,48 +55,77 @@ impl<'a> Calendar<'a> {
     self.block = Some(block);
     self
   }
+
+  /// Shades each day by its number of commits, with a legend and, when there
+  /// is room, a view of the weeks leading up to the selected day.
+  pub fn activity(mut self, activity: HashMap<NaiveDate, usize>) -> Self {
+    self.activity = Some(activity);
+    self
+  }
+
+  pub fn activity_colors(mut self, colors: [Color; 5]) -> Self {
+    self.activity_colors = colors;
+    self
+  }
+
+  pub fn activity_text_color(mut self, color: Color) -> Self {
+    self.activity_text_color = color;
+    self
+  }
+
+  pub fn range_style(mut self, style: Style) -> Self {
+    self.range_style = style;
+    self
+  }
+
+  pub fn selected_style(mut self, style: Style) -> Self {
+    self.selected_style = style;
+    self
+  }
+
+  /// The shade of a day with `count` commits.
+  fn shade(&self, date: NaiveDate) -> Option<Color> {
+    let activity = self.activity.as_ref()?;
+    let max = activity.values().copied().max().unwrap_or_default();
+    let count = activity.get(&date).copied().unwrap_or_default();
+    Some(self.activity_colors[level(count, max)])
+  }
+
+  /// The day in the first column of each week. Defaults to Sunday.
+  pub fn first_weekday(mut self, weekday: Weekday) -> Self {
+    self.first_weekday = weekday;
+    self
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CalendarState {
+  /// The day under the cursor.
   datetime: NaiveDate,
+  /// The other end of a range of days, when one is being selected.
+  anchor: Option<NaiveDate>,
 }
 
 impl From<&DateTime<Utc>> for CalendarState {
   fn from(datetime: &DateTime<Utc>) -> Self {
     Self {
-      datetime: datetime.date_naive(),
+      datetime: datetime.with_timezone(&Local).date_naive(),
+      anchor: None,
     }
   }
 }
 
 impl From<&CalendarState> for DateTime<Utc> {
   fn from(state: &CalendarState) -> Self {
-    let mut datetime = chrono::Utc::now();
-    datetime = datetime
-      .with_hour(0)
-      .unwrap()
-      .with_minute(0)
-      .unwrap()
-      .with_second(0)
-      .unwrap();
-
-    datetime = datetime
-      .with_year(state.datetime.year())
-      .unwrap()
-      .with_month(state.datetime.month())
-      .unwrap()
-      .with_day(state.datetime.day())
-      .unwrap();
-
-    datetime
+    start_of_day(state.datetime)
   }
 }
 
 impl CalendarState {
   pub fn today() -> Self {
     Self {
-      datetime: chrono::Utc::now().date_naive(),
+      datetime: Local::now().date_naive(),
+      anchor: None,
     }
   }
 
@@ -68,6 +133,28 @@ impl CalendarState {
     self.into()
   }
 
+  /// The start of the day after the day under the cursor, ie its end.
+  pub fn end_of_day(&self) -> DateTime<Utc> {
+    start_of_day(self.datetime + Duration::try_days(1).unwrap())
+  }
+
+  /// The start of the first selected day and, when a range is selected, the
+  /// start of the day after the last.
+  pub fn selection(&self) -> (DateTime<Utc>, Option<DateTime<Utc>>) {
+    let (first, last) = self.range();
+    let end = self
+      .anchor
+      .map(|_| start_of_day(last + Duration::try_days(1).unwrap()));
+    (start_of_day(first), end)
+  }
+
+  /// The first and last selected days, which are the same day unless a range
+  /// is being selected.
+  fn range(&self) -> (NaiveDate, NaiveDate) {
+    let anchor = self.anchor.unwrap_or(self.datetime);
+    (anchor.min(self.datetime), anchor.max(self.datetime))
+  }
+
   pub fn decrement(&mut self, days: i64) {
     self.datetime -= Duration::try_days(days).unwrap();
   }
@@ -75,6 +162,35 @@ impl CalendarState {
   pub fn increment(&mut self, days: i64) {
     self.datetime += Duration::try_days(days).unwrap();
   }
+
+  /// Moves back by `months`, to the last day of the month when the day is
+  /// past its end.
+  pub fn decrement_months(&mut self, months: u32) {
+    self.datetime = self.datetime - Months::new(months);
+  }
+
+  /// Moves forward by `months`, to the last day of the month when the day is
+  /// past its end.
+  pub fn increment_months(&mut self, months: u32) {
+    self.datetime = self.datetime + Months::new(months);
+  }
+
+  pub fn go_to_today(&mut self) {
+    self.datetime = Local::now().date_naive();
+  }
+
+  /// Starts selecting a range from the day under the cursor, or goes back to
+  /// selecting a single day.
+  pub fn toggle_anchor(&mut self) {
+    self.anchor = match self.anchor {
+      Some(_) => None,
+      None => Some(self.datetime),
+    };
+  }
+
+  pub fn is_range(&self) -> bool {
+    self.anchor.is_some()
+  }
 }
 
 impl StatefulWidget for Calendar<'_> {
@@ -95,10 +211,15 @@ impl StatefulWidget for Calendar<'_> {
       None => area,
     };
 
-    overheader(buf, calendar_area, state.datetime);
-    header(buf, calendar_area, state.datetime);
+    let month_area = Rect {
+      width: calendar_area.width.min(MONTH_WIDTH),
+      ..calendar_area
+    };
+    overheader(buf, month_area, state.datetime);
+    header(buf, month_area, self.first_weekday);
 
-    let today = chrono::Utc::now().date_naive();
+    let today = Local::now().date_naive();
+    let (first, last) = state.range();
     let days_in_month = total_days_in_month(state.datetime.year(), state.datetime.month()).unwrap();
     let mut current_date = state.datetime.with_day(1).unwrap();
 
@@ -108,21 +229,27 @@ impl StatefulWidget for Calendar<'_> {
     while printing {
       let day_of_week = current_date.weekday();
       let day = current_date.day();
-      let y = area.y + 2 + printing_week;
-      let x = area.x + 2 + day_x_offset(day, day_of_week);
+      let y = month_area.y + 1 + printing_week;
+      let x = month_area.x + 1 + day_x_offset(day, day_of_week, self.first_weekday);
 
       let mut style = Style::default();
 
+      if let Some(color) = self.shade(current_date)
+        && color != self.activity_colors[0]
+      {
+        style = style.bg(color).fg(self.activity_text_color);
+      }
+
       if current_date == today {
         style = style
           .add_modifier(Modifier::UNDERLINED)
           .add_modifier(Modifier::BOLD);
       };
+      if state.is_range() && (first..=last).contains(&current_date) {
+        style = style.patch(self.range_style);
+      }
       if day == state.datetime.day() {
-        style = style
-          .bg(Color::LightRed)
-          .fg(Color::Black)
-          .add_modifier(Modifier::BOLD);
+        style = style.patch(self.selected_style);
       };
 
       buf.set_string(x, y, format!("{day}"), style);
@@ -130,12 +257,99 @@ impl StatefulWidget for Calendar<'_> {
       if day == days_in_month {
         printing = false;
       } else {
-        if day_of_week == Weekday::Sat {
+        if day_of_week == self.first_weekday.pred() {
           printing_week += 1;
         }
         current_date += Duration::try_days(1).unwrap();
       }
     }
+
+    if self.activity.is_some() {
+      if month_area.height > 8 {
+        self.legend(buf, month_area.x + 1, month_area.y + 8);
+      }
+      let weeks_area = Rect {
+        x: month_area.right() + 2,
+        width: calendar_area.right().saturating_sub(month_area.right() + 2),
+        ..calendar_area
+      };
+      self.weeks(buf, weeks_area, state);
+    }
+  }
+}
+
+impl Calendar<'_> {
+  fn legend(&self, buf: &mut Buffer, x: u16, y: u16) {
+    buf.set_string(x, y, "Less", Style::default());
+    for (index, color) in self.activity_colors.iter().enumerate() {
+      buf.set_string(x + 5 + 2 * index as u16, y, "■", Style::default().fg(*color));
+    }
+    buf.set_string(x + 15, y, "More", Style::default());
+  }
+
+  /// A GitHub style grid with a column for each week up to the selected day,
+  /// and a row for each day of the week, when there is room for it.
+  fn weeks(&self, buf: &mut Buffer, area: Rect, state: &CalendarState) {
+    if area.height < 8 || area.width < 3 + 2 * MIN_WEEKS {
+      return;
+    }
+    let weeks = ((area.width - 3) / 2).min(MAX_WEEKS);
+    let today = Local::now().date_naive();
+    let (first, last) = state.range();
+    let last_week = state.datetime
+      - Duration::try_days(
+        state
+          .datetime
+          .weekday()
+          .days_since(self.first_weekday)
+          .into(),
+      )
+      .unwrap();
+
+    let mut weekday = self.first_weekday;
+    for row in 0..7 {
+      buf.set_string(area.x, area.y + 1 + row, &weekday.to_string()[..2], Style::default());
+      weekday = weekday.succ();
+    }
+
+    let mut label_end = area.x;
+    for column in 0..weeks {
+      let week = last_week - Duration::try_weeks((weeks - 1 - column).into()).unwrap();
+      let x = area.x + 3 + 2 * column;
+
+      // Label the first week of each month, when it does not overlap the last label.
+      let month_start = (0..7)
+        .map(|day| week + Duration::try_days(day).unwrap())
+        .find(|date| date.day() == 1);
+      if let Some(date) = month_start
+        && x >= label_end
+        && x + 3 <= area.right()
+      {
+        buf.set_string(x, area.y, date.format("%b").to_string(), Style::default());
+        label_end = x + 4;
+      }
+
+      for row in 0..7 {
+        let date = week + Duration::try_days(row.into()).unwrap();
+        if date > today && date != state.datetime {
+          continue;
+        }
+        let mut style = Style::default().fg(self.shade(date).unwrap_or(self.activity_colors[0]));
+        // Only the backgrounds, as the square itself is shaded by activity.
+        if state.is_range()
+          && (first..=last).contains(&date)
+          && let Some(bg) = self.range_style.bg
+        {
+          style = style.bg(bg);
+        }
+        if date == state.datetime
+          && let Some(bg) = self.selected_style.bg
+        {
+          style = style.bg(bg);
+        }
+        buf.set_string(x, area.y + 1 + row, "■", style);
+      }
+    }
   }
 }
 
@@ -156,27 +370,42 @@ fn overheader(buf: &mut Buffer, area: Rect, datetime: NaiveDate) {
   buf.set_string(x, area.y, text, Style::default());
 }
 
-fn header(buf: &mut Buffer, area: Rect, _datetime: NaiveDate) {
+fn header(buf: &mut Buffer, area: Rect, first_weekday: Weekday) {
   let header_style = Style::default().add_modifier(Modifier::UNDERLINED);
-  let text = "Su Mo Tu We Th Fr Sa";
+  let text = std::iter::successors(Some(first_weekday), |weekday| Some(weekday.succ()))
+    .take(7)
+    .map(|weekday| weekday.to_string()[..2].to_string())
+    .collect::<Vec<_>>()
+    .join(" ");
   let x = centered_line_offset(text.len().try_into().unwrap(), area.width, area.x);
   buf.set_string(x, area.y + 1, text, header_style);
 }
 
-fn day_x_offset(day: u32, weekday: Weekday) -> u16 {
-  let weekday_offset = match weekday {
-    Weekday::Sun => 0,
-    Weekday::Mon => 3,
-    Weekday::Tue => 6,
-    Weekday::Wed => 9,
-    Weekday::Thu => 12,
-    Weekday::Fri => 15,
-    Weekday::Sat => 18,
-  };
+fn day_x_offset(day: u32, weekday: Weekday, first_weekday: Weekday) -> u16 {
+  let weekday_offset = u16::try_from(weekday.days_since(first_weekday)).unwrap() * 3;
 
   if day < 10 { weekday_offset + 1 } else { weekday_offset }
 }
 
+/// The shade of `count` commits, from 0 for none up to 4 for `max`.
+fn level(count: usize, max: usize) -> usize {
+  if count == 0 || max == 0 {
+    0
+  } else {
+    (count * 4).div_ceil(max).clamp(1, 4)
+  }
+}
+
+/// The start of `date` in the local timezone, like the days of `since` and
+/// `until`.
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+  let midnight = date.and_time(NaiveTime::MIN);
+  Local
+    .from_local_datetime(&midnight)
+    .earliest()
+    .map_or_else(|| midnight.and_utc(), |start| start.with_timezone(&Utc))
+}
+
 fn total_days_in_month(year: i32, month: u32) -> Option<u32> {
   let days = NaiveDate::from_ymd_opt(
     match month {
@@ -195,3 +424,114 @@ fn total_days_in_month(year: i32, month: u32) -> Option<u32> {
 
   u32::try_from(days).ok()
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn state(year: i32, month: u32, day: u32) -> CalendarState {
+    CalendarState {
+      datetime: NaiveDate::from_ymd_opt(year, month, day).unwrap(),
+      anchor: None,
+    }
+  }
+
+  fn render(calendar: Calendar, state: &mut CalendarState) -> Buffer {
+    let area = Rect::new(0, 0, 22, 10);
+    let mut buf = Buffer::empty(area);
+    StatefulWidget::render(calendar, area, &mut buf, state);
+    buf
+  }
+
+  fn row(buf: &Buffer, y: u16) -> String {
+    (0..buf.area.width)
+      .map(|x| buf[(x, y)].symbol())
+      .collect::<String>()
+      .trim_end()
+      .to_string()
+  }
+
+  #[test]
+  fn calendar_starts_weeks_on_first_weekday() {
+    // 1 October 2026 is a Thursday.
+    let buf = render(Calendar::new(), &mut state(2026, 10, 19));
+    assert_eq!(row(&buf, 1), " Su Mo Tu We Th Fr Sa");
+    assert_eq!(row(&buf, 2), "              1  2  3");
+
+    let buf = render(Calendar::new().first_weekday(Weekday::Mon), &mut state(2026, 10, 19));
+    assert_eq!(row(&buf, 1), " Mo Tu We Th Fr Sa Su");
+    assert_eq!(row(&buf, 2), "           1  2  3  4");
+    assert_eq!(row(&buf, 3), "  5  6  7  8  9 10 11");
+  }
+
+  #[test]
+  fn calendar_shades_activity() {
+    let activity = HashMap::from([
+      (NaiveDate::from_ymd_opt(2026, 10, 5).unwrap(), 1),
+      (NaiveDate::from_ymd_opt(2026, 10, 6).unwrap(), 8),
+    ]);
+    let colors = crate::theme::ColorTheme::try_from(crate::theme::Theme::Dark)
+      .unwrap()
+      .calendar_activity_colors;
+    let calendar = Calendar::new()
+      .first_weekday(Weekday::Mon)
+      .activity(activity)
+      .activity_colors(colors)
+      .selected_style(Style::default().bg(Color::Blue));
+
+    let buf = render(calendar, &mut state(2026, 10, 19));
+    // The 5th and 6th start the second week, and the 19th the fourth.
+    assert_eq!(buf[(2, 3)].bg, colors[1]);
+    assert_eq!(buf[(2, 5)].bg, Color::Blue);
+    assert_eq!(buf[(5, 3)].bg, colors[4]);
+    assert_eq!(buf[(8, 3)].bg, Color::Reset);
+    assert_eq!(row(&buf, 8), " Less ■ ■ ■ ■ ■ More");
+  }
+
+  #[test]
+  fn calendar_shows_weeks_when_there_is_room() {
+    let area = Rect::new(0, 0, 24 + 3 + 2 * 10, 10);
+    let mut buf = Buffer::empty(area);
+    let calendar = Calendar::new().activity(HashMap::new());
+    StatefulWidget::render(calendar, area, &mut buf, &mut state(2026, 10, 19));
+    assert!(row(&buf, 1).starts_with(" Su Mo Tu We Th Fr Sa   Su ■ ■"));
+    assert!(row(&buf, 0).contains("Sep"));
+    assert!(row(&buf, 0).contains("Oct"));
+
+    let buf = render(Calendar::new().activity(HashMap::new()), &mut state(2026, 10, 19));
+    assert_eq!(row(&buf, 1), " Su Mo Tu We Th Fr Sa");
+  }
+
+  #[test]
+  fn activity_levels() {
+    assert_eq!(level(0, 10), 0);
+    assert_eq!(level(1, 10), 1);
+    assert_eq!(level(5, 10), 2);
+    assert_eq!(level(10, 10), 4);
+  }
+
+  #[test]
+  fn calendar_state_moves_by_month() {
+    let mut calendar = state(2026, 1, 31);
+    calendar.increment_months(1);
+    assert_eq!(calendar, state(2026, 2, 28));
+    calendar.decrement_months(2);
+    assert_eq!(calendar, state(2025, 12, 28));
+  }
+
+  #[test]
+  fn calendar_state_selects_range() {
+    let midnight = |day| {
+      Local
+        .with_ymd_and_hms(2026, 10, day, 0, 0, 0)
+        .unwrap()
+        .with_timezone(&Utc)
+    };
+    let mut calendar = state(2026, 10, 13);
+    assert_eq!(calendar.selection(), (midnight(13), None));
+
+    calendar.toggle_anchor();
+    calendar.decrement(2);
+    assert_eq!(calendar.selection(), (midnight(11), Some(midnight(14))));
+  }
+}